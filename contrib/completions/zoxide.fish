@@ -27,37 +27,69 @@ end
 complete -c zoxide -n "__fish_zoxide_needs_command" -s h -l help -d 'Print help'
 complete -c zoxide -n "__fish_zoxide_needs_command" -s V -l version -d 'Print version'
 complete -c zoxide -n "__fish_zoxide_needs_command" -f -a "add" -d 'Add a new directory or increment its rank'
+complete -c zoxide -n "__fish_zoxide_needs_command" -f -a "alias" -d 'Manage named aliases for directories'
 complete -c zoxide -n "__fish_zoxide_needs_command" -f -a "edit" -d 'Edit the database'
+complete -c zoxide -n "__fish_zoxide_needs_command" -f -a "export" -d 'Export the database to another format'
 complete -c zoxide -n "__fish_zoxide_needs_command" -f -a "import" -d 'Import entries from another application'
 complete -c zoxide -n "__fish_zoxide_needs_command" -f -a "init" -d 'Generate shell configuration'
+complete -c zoxide -n "__fish_zoxide_needs_command" -f -a "migrate" -d 'Convert the database to another format version'
+complete -c zoxide -n "__fish_zoxide_needs_command" -f -a "mv" -d 'Update the database after moving a directory'
+complete -c zoxide -n "__fish_zoxide_needs_command" -f -a "preview" -d 'Show the rank, last access time, and contents of a directory'
 complete -c zoxide -n "__fish_zoxide_needs_command" -f -a "query" -d 'Search for a directory in the database'
 complete -c zoxide -n "__fish_zoxide_needs_command" -f -a "remove" -d 'Remove a directory from the database'
+complete -c zoxide -n "__fish_zoxide_needs_command" -f -a "stats" -d 'Show a summary of the database'
 complete -c zoxide -n "__fish_zoxide_using_subcommand add" -s s -l score -d 'The rank to increment the entry if it exists or initialize it with if it doesn\'t' -r
+complete -c zoxide -n "__fish_zoxide_using_subcommand add" -l stdin0 -d 'Also read NUL-separated paths from stdin, allowing paths that contain newlines'
 complete -c zoxide -n "__fish_zoxide_using_subcommand add" -s h -l help -d 'Print help'
 complete -c zoxide -n "__fish_zoxide_using_subcommand add" -s V -l version -d 'Print version'
-complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and not __fish_seen_subcommand_from decrement delete increment reload" -s h -l help -d 'Print help'
-complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and not __fish_seen_subcommand_from decrement delete increment reload" -s V -l version -d 'Print version'
-complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and not __fish_seen_subcommand_from decrement delete increment reload" -f -a "decrement"
-complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and not __fish_seen_subcommand_from decrement delete increment reload" -f -a "delete"
-complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and not __fish_seen_subcommand_from decrement delete increment reload" -f -a "increment"
-complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and not __fish_seen_subcommand_from decrement delete increment reload" -f -a "reload"
+complete -c zoxide -n "__fish_zoxide_using_subcommand alias; and not __fish_seen_subcommand_from add list remove" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c zoxide -n "__fish_zoxide_using_subcommand alias; and not __fish_seen_subcommand_from add list remove" -s V -l version -d 'Print version'
+complete -c zoxide -n "__fish_zoxide_using_subcommand alias; and not __fish_seen_subcommand_from add list remove" -f -a "add" -d 'Create an alias, or change the directory it points to'
+complete -c zoxide -n "__fish_zoxide_using_subcommand alias; and not __fish_seen_subcommand_from add list remove" -f -a "list" -d 'List all aliases'
+complete -c zoxide -n "__fish_zoxide_using_subcommand alias; and not __fish_seen_subcommand_from add list remove" -f -a "remove" -d 'Remove an alias'
+complete -c zoxide -n "__fish_zoxide_using_subcommand alias; and __fish_seen_subcommand_from add" -s h -l help -d 'Print help'
+complete -c zoxide -n "__fish_zoxide_using_subcommand alias; and __fish_seen_subcommand_from add" -s V -l version -d 'Print version'
+complete -c zoxide -n "__fish_zoxide_using_subcommand alias; and __fish_seen_subcommand_from list" -s h -l help -d 'Print help'
+complete -c zoxide -n "__fish_zoxide_using_subcommand alias; and __fish_seen_subcommand_from list" -s V -l version -d 'Print version'
+complete -c zoxide -n "__fish_zoxide_using_subcommand alias; and __fish_seen_subcommand_from remove" -s h -l help -d 'Print help'
+complete -c zoxide -n "__fish_zoxide_using_subcommand alias; and __fish_seen_subcommand_from remove" -s V -l version -d 'Print version'
+complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and not __fish_seen_subcommand_from decrement delete increment pin reload unpin" -s h -l help -d 'Print help'
+complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and not __fish_seen_subcommand_from decrement delete increment pin reload unpin" -s V -l version -d 'Print version'
+complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and not __fish_seen_subcommand_from decrement delete increment pin reload unpin" -f -a "decrement"
+complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and not __fish_seen_subcommand_from decrement delete increment pin reload unpin" -f -a "delete"
+complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and not __fish_seen_subcommand_from decrement delete increment pin reload unpin" -f -a "increment"
+complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and not __fish_seen_subcommand_from decrement delete increment pin reload unpin" -f -a "pin"
+complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and not __fish_seen_subcommand_from decrement delete increment pin reload unpin" -f -a "reload"
+complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and not __fish_seen_subcommand_from decrement delete increment pin reload unpin" -f -a "unpin"
 complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and __fish_seen_subcommand_from decrement" -s h -l help -d 'Print help'
 complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and __fish_seen_subcommand_from decrement" -s V -l version -d 'Print version'
 complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and __fish_seen_subcommand_from delete" -s h -l help -d 'Print help'
 complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and __fish_seen_subcommand_from delete" -s V -l version -d 'Print version'
 complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and __fish_seen_subcommand_from increment" -s h -l help -d 'Print help'
 complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and __fish_seen_subcommand_from increment" -s V -l version -d 'Print version'
+complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and __fish_seen_subcommand_from pin" -s h -l help -d 'Print help'
+complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and __fish_seen_subcommand_from pin" -s V -l version -d 'Print version'
 complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and __fish_seen_subcommand_from reload" -s h -l help -d 'Print help'
 complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and __fish_seen_subcommand_from reload" -s V -l version -d 'Print version'
-complete -c zoxide -n "__fish_zoxide_using_subcommand import; and not __fish_seen_subcommand_from atuin autojump fasd z z.lua zsh-z" -l merge -d 'Merge into existing database'
-complete -c zoxide -n "__fish_zoxide_using_subcommand import; and not __fish_seen_subcommand_from atuin autojump fasd z z.lua zsh-z" -s h -l help -d 'Print help'
-complete -c zoxide -n "__fish_zoxide_using_subcommand import; and not __fish_seen_subcommand_from atuin autojump fasd z z.lua zsh-z" -s V -l version -d 'Print version'
-complete -c zoxide -n "__fish_zoxide_using_subcommand import; and not __fish_seen_subcommand_from atuin autojump fasd z z.lua zsh-z" -f -a "atuin" -d 'Import from atuin'
-complete -c zoxide -n "__fish_zoxide_using_subcommand import; and not __fish_seen_subcommand_from atuin autojump fasd z z.lua zsh-z" -f -a "autojump" -d 'Import from autojump'
-complete -c zoxide -n "__fish_zoxide_using_subcommand import; and not __fish_seen_subcommand_from atuin autojump fasd z z.lua zsh-z" -f -a "fasd" -d 'Import from fasd'
-complete -c zoxide -n "__fish_zoxide_using_subcommand import; and not __fish_seen_subcommand_from atuin autojump fasd z z.lua zsh-z" -f -a "z" -d 'Import from z'
-complete -c zoxide -n "__fish_zoxide_using_subcommand import; and not __fish_seen_subcommand_from atuin autojump fasd z z.lua zsh-z" -f -a "z.lua" -d 'Import from z.lua'
-complete -c zoxide -n "__fish_zoxide_using_subcommand import; and not __fish_seen_subcommand_from atuin autojump fasd z z.lua zsh-z" -f -a "zsh-z" -d 'Import from zsh-z'
+complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and __fish_seen_subcommand_from unpin" -s h -l help -d 'Print help'
+complete -c zoxide -n "__fish_zoxide_using_subcommand edit; and __fish_seen_subcommand_from unpin" -s V -l version -d 'Print version'
+complete -c zoxide -n "__fish_zoxide_using_subcommand export" -s f -l format -d 'Format to write the entries in' -r -f -a "autojump\t'autojump\'s data file (`rank<TAB>path`)'
+csv\t'CSV, with a header row'
+json\t'A JSON array of entries'
+jsonl\t'One JSON entry per line'
+z\t'z\'s data file (`path|rank|last_accessed`), also used by fasd, z.lua and zsh-z'"
+complete -c zoxide -n "__fish_zoxide_using_subcommand export" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c zoxide -n "__fish_zoxide_using_subcommand export" -s V -l version -d 'Print version'
+complete -c zoxide -n "__fish_zoxide_using_subcommand import; and not __fish_seen_subcommand_from atuin autojump fasd json z z.lua zsh-z" -l merge -d 'Merge into existing database'
+complete -c zoxide -n "__fish_zoxide_using_subcommand import; and not __fish_seen_subcommand_from atuin autojump fasd json z z.lua zsh-z" -s h -l help -d 'Print help'
+complete -c zoxide -n "__fish_zoxide_using_subcommand import; and not __fish_seen_subcommand_from atuin autojump fasd json z z.lua zsh-z" -s V -l version -d 'Print version'
+complete -c zoxide -n "__fish_zoxide_using_subcommand import; and not __fish_seen_subcommand_from atuin autojump fasd json z z.lua zsh-z" -f -a "atuin" -d 'Import from atuin'
+complete -c zoxide -n "__fish_zoxide_using_subcommand import; and not __fish_seen_subcommand_from atuin autojump fasd json z z.lua zsh-z" -f -a "autojump" -d 'Import from autojump'
+complete -c zoxide -n "__fish_zoxide_using_subcommand import; and not __fish_seen_subcommand_from atuin autojump fasd json z z.lua zsh-z" -f -a "fasd" -d 'Import from fasd'
+complete -c zoxide -n "__fish_zoxide_using_subcommand import; and not __fish_seen_subcommand_from atuin autojump fasd json z z.lua zsh-z" -f -a "json" -d 'Import from a JSON array or JSON-lines file, as written by `zoxide export`'
+complete -c zoxide -n "__fish_zoxide_using_subcommand import; and not __fish_seen_subcommand_from atuin autojump fasd json z z.lua zsh-z" -f -a "z" -d 'Import from z'
+complete -c zoxide -n "__fish_zoxide_using_subcommand import; and not __fish_seen_subcommand_from atuin autojump fasd json z z.lua zsh-z" -f -a "z.lua" -d 'Import from z.lua'
+complete -c zoxide -n "__fish_zoxide_using_subcommand import; and not __fish_seen_subcommand_from atuin autojump fasd json z z.lua zsh-z" -f -a "zsh-z" -d 'Import from zsh-z'
 complete -c zoxide -n "__fish_zoxide_using_subcommand import; and __fish_seen_subcommand_from atuin" -l merge -d 'Merge into existing database'
 complete -c zoxide -n "__fish_zoxide_using_subcommand import; and __fish_seen_subcommand_from atuin" -s h -l help -d 'Print help'
 complete -c zoxide -n "__fish_zoxide_using_subcommand import; and __fish_seen_subcommand_from atuin" -s V -l version -d 'Print version'
@@ -67,6 +99,9 @@ complete -c zoxide -n "__fish_zoxide_using_subcommand import; and __fish_seen_su
 complete -c zoxide -n "__fish_zoxide_using_subcommand import; and __fish_seen_subcommand_from fasd" -l merge -d 'Merge into existing database'
 complete -c zoxide -n "__fish_zoxide_using_subcommand import; and __fish_seen_subcommand_from fasd" -s h -l help -d 'Print help'
 complete -c zoxide -n "__fish_zoxide_using_subcommand import; and __fish_seen_subcommand_from fasd" -s V -l version -d 'Print version'
+complete -c zoxide -n "__fish_zoxide_using_subcommand import; and __fish_seen_subcommand_from json" -l merge -d 'Merge into existing database'
+complete -c zoxide -n "__fish_zoxide_using_subcommand import; and __fish_seen_subcommand_from json" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c zoxide -n "__fish_zoxide_using_subcommand import; and __fish_seen_subcommand_from json" -s V -l version -d 'Print version'
 complete -c zoxide -n "__fish_zoxide_using_subcommand import; and __fish_seen_subcommand_from z" -l merge -d 'Merge into existing database'
 complete -c zoxide -n "__fish_zoxide_using_subcommand import; and __fish_seen_subcommand_from z" -s h -l help -d 'Print help'
 complete -c zoxide -n "__fish_zoxide_using_subcommand import; and __fish_seen_subcommand_from z" -s V -l version -d 'Print version'
@@ -83,13 +118,38 @@ pwd\t''"
 complete -c zoxide -n "__fish_zoxide_using_subcommand init" -l no-cmd -d 'Prevents zoxide from defining the `z` and `zi` commands'
 complete -c zoxide -n "__fish_zoxide_using_subcommand init" -s h -l help -d 'Print help'
 complete -c zoxide -n "__fish_zoxide_using_subcommand init" -s V -l version -d 'Print version'
+complete -c zoxide -n "__fish_zoxide_using_subcommand migrate" -l to -d 'The version to convert to (defaults to the latest)' -r
+complete -c zoxide -n "__fish_zoxide_using_subcommand migrate" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c zoxide -n "__fish_zoxide_using_subcommand migrate" -s V -l version -d 'Print version'
+complete -c zoxide -n "__fish_zoxide_using_subcommand mv" -s n -l dry-run -d 'List the entries that would be moved, without changing the database'
+complete -c zoxide -n "__fish_zoxide_using_subcommand mv" -s h -l help -d 'Print help (see more with \'--help\')'
+complete -c zoxide -n "__fish_zoxide_using_subcommand mv" -s V -l version -d 'Print version'
+complete -c zoxide -n "__fish_zoxide_using_subcommand preview" -s h -l help -d 'Print help'
+complete -c zoxide -n "__fish_zoxide_using_subcommand preview" -s V -l version -d 'Print version'
+complete -c zoxide -n "__fish_zoxide_using_subcommand query" -l format -d 'Format to print results in' -r -f -a "text\t'Paths, with scores if `--score` is given'
+json\t'A JSON object, or an array of them with `--list`'
+jsonl\t'One JSON object per line'"
 complete -c zoxide -n "__fish_zoxide_using_subcommand query" -l exclude -d 'Exclude the current directory' -r -f -a "(__fish_complete_directories)"
 complete -c zoxide -n "__fish_zoxide_using_subcommand query" -l base-dir -d 'Only search within this directory' -r -f -a "(__fish_complete_directories)"
 complete -c zoxide -n "__fish_zoxide_using_subcommand query" -s a -l all -d 'Show unavailable directories'
 complete -c zoxide -n "__fish_zoxide_using_subcommand query" -s i -l interactive -d 'Use interactive selection'
 complete -c zoxide -n "__fish_zoxide_using_subcommand query" -s l -l list -d 'List all matching directories'
+complete -c zoxide -n "__fish_zoxide_using_subcommand query" -s m -l multi -d 'Select several directories, then print them with Enter, remove them from the database with Ctrl-D, or open them with Ctrl-O'
+complete -c zoxide -n "__fish_zoxide_using_subcommand query" -l explain -d 'Explain how each listed directory was ranked, and why any were filtered out'
+complete -c zoxide -n "__fish_zoxide_using_subcommand query" -s 0 -l print0 -d 'Separate listed or selected directories with NUL instead of newline'
+complete -c zoxide -n "__fish_zoxide_using_subcommand query" -l parent -d 'Search the parents of the current directory first, returning the nearest one that matches'
 complete -c zoxide -n "__fish_zoxide_using_subcommand query" -s s -l score -d 'Print score with results'
-complete -c zoxide -n "__fish_zoxide_using_subcommand query" -s h -l help -d 'Print help'
+complete -c zoxide -n "__fish_zoxide_using_subcommand query" -l fuzzy -d 'Match keywords fuzzily, and rank results by how well they match'
+complete -c zoxide -n "__fish_zoxide_using_subcommand query" -l regex -d 'Treat keywords as regular expressions'
+complete -c zoxide -n "__fish_zoxide_using_subcommand query" -l smart-case -d 'Match keywords case-sensitively if they contain uppercase letters'
+complete -c zoxide -n "__fish_zoxide_using_subcommand query" -s h -l help -d 'Print help (see more with \'--help\')'
 complete -c zoxide -n "__fish_zoxide_using_subcommand query" -s V -l version -d 'Print version'
+complete -c zoxide -n "__fish_zoxide_using_subcommand remove" -l forget -d 'Prevent the directories from being added again (paths may be globs)'
+complete -c zoxide -n "__fish_zoxide_using_subcommand remove" -l forgotten -d 'List directories that have been forgotten'
+complete -c zoxide -n "__fish_zoxide_using_subcommand remove" -l unforget -d 'Allow forgotten directories to be added again'
+complete -c zoxide -n "__fish_zoxide_using_subcommand remove" -l stdin0 -d 'Also read NUL-separated paths from stdin'
 complete -c zoxide -n "__fish_zoxide_using_subcommand remove" -s h -l help -d 'Print help'
 complete -c zoxide -n "__fish_zoxide_using_subcommand remove" -s V -l version -d 'Print version'
+complete -c zoxide -n "__fish_zoxide_using_subcommand stats" -s n -l top -d 'Number of directories to show in each top list' -r
+complete -c zoxide -n "__fish_zoxide_using_subcommand stats" -s h -l help -d 'Print help'
+complete -c zoxide -n "__fish_zoxide_using_subcommand stats" -s V -l version -d 'Print version'
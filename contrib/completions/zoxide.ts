@@ -15,6 +15,10 @@ const completion: Fig.Spec = {
             isOptional: true,
           },
         },
+        {
+          name: "--stdin0",
+          description: "Also read NUL-separated paths from stdin, allowing paths that contain newlines",
+        },
         {
           name: ["-h", "--help"],
           description: "Print help",
@@ -27,9 +31,80 @@ const completion: Fig.Spec = {
       args: {
         name: "paths",
         isVariadic: true,
+        isOptional: true,
         template: "folders",
       },
     },
+    {
+      name: "alias",
+      description: "Manage named aliases for directories",
+      subcommands: [
+        {
+          name: "add",
+          description: "Create an alias, or change the directory it points to",
+          options: [
+            {
+              name: ["-h", "--help"],
+              description: "Print help",
+            },
+            {
+              name: ["-V", "--version"],
+              description: "Print version",
+            },
+          ],
+          args: [
+            {
+              name: "name",
+            },
+            {
+              name: "path",
+              template: "folders",
+            },
+          ]
+        },
+        {
+          name: "list",
+          description: "List all aliases",
+          options: [
+            {
+              name: ["-h", "--help"],
+              description: "Print help",
+            },
+            {
+              name: ["-V", "--version"],
+              description: "Print version",
+            },
+          ],
+        },
+        {
+          name: "remove",
+          description: "Remove an alias",
+          options: [
+            {
+              name: ["-h", "--help"],
+              description: "Print help",
+            },
+            {
+              name: ["-V", "--version"],
+              description: "Print version",
+            },
+          ],
+          args: {
+            name: "name",
+          },
+        },
+      ],
+      options: [
+        {
+          name: ["-h", "--help"],
+          description: "Print help (see more with '--help')",
+        },
+        {
+          name: ["-V", "--version"],
+          description: "Print version",
+        },
+      ],
+    },
     {
       name: "edit",
       description: "Edit the database",
@@ -85,6 +160,23 @@ const completion: Fig.Spec = {
             name: "path",
           },
         },
+        {
+          name: "pin",
+          hidden: true,
+          options: [
+            {
+              name: ["-h", "--help"],
+              description: "Print help",
+            },
+            {
+              name: ["-V", "--version"],
+              description: "Print version",
+            },
+          ],
+          args: {
+            name: "path",
+          },
+        },
         {
           name: "reload",
           hidden: true,
@@ -99,6 +191,23 @@ const completion: Fig.Spec = {
             },
           ],
         },
+        {
+          name: "unpin",
+          hidden: true,
+          options: [
+            {
+              name: ["-h", "--help"],
+              description: "Print help",
+            },
+            {
+              name: ["-V", "--version"],
+              description: "Print version",
+            },
+          ],
+          args: {
+            name: "path",
+          },
+        },
       ],
       options: [
         {
@@ -111,6 +220,51 @@ const completion: Fig.Spec = {
         },
       ],
     },
+    {
+      name: "export",
+      description: "Export the database to another format",
+      options: [
+        {
+          name: ["-f", "--format"],
+          description: "Format to write the entries in",
+          isRepeatable: true,
+          args: {
+            name: "format",
+            isOptional: true,
+            suggestions: [
+              {
+                name: "autojump",
+                description: "autojump's data file (`rank<TAB>path`)",
+              },
+              {
+                name: "csv",
+                description: "CSV, with a header row",
+              },
+              {
+                name: "json",
+                description: "A JSON array of entries",
+              },
+              {
+                name: "jsonl",
+                description: "One JSON entry per line",
+              },
+              {
+                name: "z",
+                description: "z's data file (`path|rank|last_accessed`), also used by fasd, z.lua and zsh-z",
+              },
+            ],
+          },
+        },
+        {
+          name: ["-h", "--help"],
+          description: "Print help (see more with '--help')",
+        },
+        {
+          name: ["-V", "--version"],
+          description: "Print version",
+        },
+      ],
+    },
     {
       name: "import",
       description: "Import entries from another application",
@@ -169,6 +323,28 @@ const completion: Fig.Spec = {
             },
           ],
         },
+        {
+          name: "json",
+          description: "Import from a JSON array or JSON-lines file, as written by `zoxide export`",
+          options: [
+            {
+              name: "--merge",
+              description: "Merge into existing database",
+            },
+            {
+              name: ["-h", "--help"],
+              description: "Print help (see more with '--help')",
+            },
+            {
+              name: ["-V", "--version"],
+              description: "Print version",
+            },
+          ],
+          args: {
+            name: "path",
+            template: "filepaths",
+          },
+        },
         {
           name: "z",
           description: "Import from z",
@@ -294,10 +470,102 @@ const completion: Fig.Spec = {
         ],
       },
     },
+    {
+      name: "migrate",
+      description: "Convert the database to another format version",
+      options: [
+        {
+          name: "--to",
+          description: "The version to convert to (defaults to the latest)",
+          isRepeatable: true,
+          args: {
+            name: "to",
+            isOptional: true,
+          },
+        },
+        {
+          name: ["-h", "--help"],
+          description: "Print help (see more with '--help')",
+        },
+        {
+          name: ["-V", "--version"],
+          description: "Print version",
+        },
+      ],
+    },
+    {
+      name: "mv",
+      description: "Update the database after moving a directory",
+      options: [
+        {
+          name: ["-n", "--dry-run"],
+          description: "List the entries that would be moved, without changing the database",
+        },
+        {
+          name: ["-h", "--help"],
+          description: "Print help (see more with '--help')",
+        },
+        {
+          name: ["-V", "--version"],
+          description: "Print version",
+        },
+      ],
+      args: [
+        {
+          name: "from",
+          template: "folders",
+        },
+        {
+          name: "to",
+          template: "folders",
+        },
+      ]
+    },
+    {
+      name: "preview",
+      description: "Show the rank, last access time, and contents of a directory",
+      options: [
+        {
+          name: ["-h", "--help"],
+          description: "Print help",
+        },
+        {
+          name: ["-V", "--version"],
+          description: "Print version",
+        },
+      ],
+      args: {
+        name: "path",
+        template: "folders",
+      },
+    },
     {
       name: "query",
       description: "Search for a directory in the database",
       options: [
+        {
+          name: "--format",
+          description: "Format to print results in",
+          isRepeatable: true,
+          args: {
+            name: "format",
+            isOptional: true,
+            suggestions: [
+              {
+                name: "text",
+                description: "Paths, with scores if `--score` is given",
+              },
+              {
+                name: "json",
+                description: "A JSON object, or an array of them with `--list`",
+              },
+              {
+                name: "jsonl",
+                description: "One JSON object per line",
+              },
+            ],
+          },
+        },
         {
           name: "--exclude",
           description: "Exclude the current directory",
@@ -338,13 +606,57 @@ const completion: Fig.Spec = {
             "--interactive",
           ],
         },
+        {
+          name: ["-m", "--multi"],
+          description: "Select several directories, then print them with Enter, remove them from the database with Ctrl-D, or open them with Ctrl-O",
+        },
+        {
+          name: "--explain",
+          description: "Explain how each listed directory was ranked, and why any were filtered out",
+          exclusiveOn: [
+            "--format",
+          ],
+        },
+        {
+          name: ["-0", "--print0"],
+          description: "Separate listed or selected directories with NUL instead of newline",
+          exclusiveOn: [
+            "--explain",
+            "--format",
+          ],
+        },
+        {
+          name: "--parent",
+          description: "Search the parents of the current directory first, returning the nearest one that matches",
+          exclusiveOn: [
+            "-i",
+            "--interactive",
+            "-l",
+            "--list",
+          ],
+        },
         {
           name: ["-s", "--score"],
           description: "Print score with results",
         },
+        {
+          name: "--fuzzy",
+          description: "Match keywords fuzzily, and rank results by how well they match",
+          exclusiveOn: [
+            "--regex",
+          ],
+        },
+        {
+          name: "--regex",
+          description: "Treat keywords as regular expressions",
+        },
+        {
+          name: "--smart-case",
+          description: "Match keywords case-sensitively if they contain uppercase letters",
+        },
         {
           name: ["-h", "--help"],
-          description: "Print help",
+          description: "Print help (see more with '--help')",
         },
         {
           name: ["-V", "--version"],
@@ -361,6 +673,32 @@ const completion: Fig.Spec = {
       name: "remove",
       description: "Remove a directory from the database",
       options: [
+        {
+          name: "--forget",
+          description: "Prevent the directories from being added again (paths may be globs)",
+          exclusiveOn: [
+            "--forgotten",
+            "--unforget",
+          ],
+        },
+        {
+          name: "--forgotten",
+          description: "List directories that have been forgotten",
+          exclusiveOn: [
+            "--unforget",
+          ],
+        },
+        {
+          name: "--unforget",
+          description: "Allow forgotten directories to be added again",
+        },
+        {
+          name: "--stdin0",
+          description: "Also read NUL-separated paths from stdin",
+          exclusiveOn: [
+            "--forgotten",
+          ],
+        },
         {
           name: ["-h", "--help"],
           description: "Print help",
@@ -377,6 +715,29 @@ const completion: Fig.Spec = {
         template: "folders",
       },
     },
+    {
+      name: "stats",
+      description: "Show a summary of the database",
+      options: [
+        {
+          name: ["-n", "--top"],
+          description: "Number of directories to show in each top list",
+          isRepeatable: true,
+          args: {
+            name: "top",
+            isOptional: true,
+          },
+        },
+        {
+          name: ["-h", "--help"],
+          description: "Print help",
+        },
+        {
+          name: ["-V", "--version"],
+          description: "Print version",
+        },
+      ],
+    },
   ],
   options: [
     {
@@ -1,4 +1,4 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use anyhow::{Result, bail};
 
@@ -9,25 +9,36 @@ use crate::{config, util};
 impl Run for Add {
     fn run(&self) -> Result<()> {
         // These characters can't be printed cleanly to a single line, so they can cause
-        // confusion when writing to stdout.
+        // confusion when writing to stdout. Paths read with --stdin0 are expected to be
+        // listed with --print0, so they are allowed there, but not in arguments.
         const EXCLUDE_CHARS: &[char] = &['\n', '\r'];
 
         let exclude_dirs = config::exclude_dirs()?;
         let max_age = config::maxage()?;
         let now = util::current_time()?;
 
+        let mut paths =
+            self.paths.iter().map(|path| (path.clone(), EXCLUDE_CHARS)).collect::<Vec<_>>();
+        if self.stdin0 {
+            let stdin = util::read_stdin0()?;
+            paths.extend(stdin.into_iter().map(|path| (PathBuf::from(path), &[][..])));
+        }
+
         let mut db = Database::open()?;
 
-        for path in &self.paths {
+        for (path, exclude_chars) in &paths {
             let path =
                 if config::resolve_symlinks() { util::canonicalize } else { util::resolve_path }(
                     path,
                 )?;
             let path = util::path_to_str(&path)?;
 
-            // Ignore path if it contains unsupported characters, or if it's in the exclude
-            // list.
-            if path.contains(EXCLUDE_CHARS) || exclude_dirs.iter().any(|glob| glob.matches(path)) {
+            // Ignore path if it contains unsupported characters, if it's in the exclude
+            // list, or if it has been forgotten.
+            if path.contains(*exclude_chars)
+                || exclude_dirs.iter().any(|glob| glob.matches(path))
+                || db.is_forgotten(path)
+            {
                 continue;
             }
             if !Path::new(path).is_dir() {
@@ -0,0 +1,53 @@
+use std::io::{self, Write};
+use std::path::Path;
+
+use anyhow::{Result, bail, ensure};
+
+use crate::cmd::{Alias, AliasCommand, Run};
+use crate::db::Database;
+use crate::error::BrokenPipeHandler;
+use crate::{config, util};
+
+impl Run for Alias {
+    fn run(&self) -> Result<()> {
+        let mut db = Database::open()?;
+
+        match &self.cmd {
+            AliasCommand::Add { name, path } => {
+                let name = name.strip_prefix('@').unwrap_or(name);
+                ensure!(
+                    !name.is_empty() && !name.contains(char::is_whitespace),
+                    "invalid alias name: {name:?}"
+                );
+
+                let path = if config::resolve_symlinks() {
+                    util::canonicalize(path)
+                } else {
+                    util::resolve_path(path)
+                }?;
+                let path = util::path_to_str(&path)?;
+                if !Path::new(path).is_dir() {
+                    bail!("not a directory: {path}");
+                }
+
+                db.add_alias(name, path);
+            }
+            AliasCommand::List => {
+                let width = db.aliases().iter().map(|alias| alias.name.len()).max().unwrap_or(0);
+                let handle = &mut io::stdout().lock();
+                for alias in db.aliases() {
+                    writeln!(handle, "@{:width$}  {}", alias.name, alias.path)
+                        .pipe_exit("stdout")?;
+                }
+            }
+            AliasCommand::Remove { name } => {
+                let name = name.strip_prefix('@').unwrap_or(name);
+                if !db.remove_alias(name) {
+                    bail!("alias not found: {name}");
+                }
+            }
+        }
+
+        db.save()
+    }
+}
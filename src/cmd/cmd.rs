@@ -3,7 +3,7 @@
 use std::path::PathBuf;
 
 use clap::builder::{IntoResettable, Resettable, StyledStr};
-use clap::{Parser, Subcommand, ValueEnum, ValueHint};
+use clap::{ArgGroup, Parser, Subcommand, ValueEnum, ValueHint};
 
 struct HelpTemplate;
 
@@ -25,9 +25,17 @@ https://github.com/ajeetdsouza/zoxide
 {tab}<bold>_ZO_DATA_DIR</bold>        {tab}Path for zoxide data files
 {tab}<bold>_ZO_ECHO</bold>            {tab}Print the matched directory before navigating to it when set to 1
 {tab}<bold>_ZO_EXCLUDE_DIRS</bold>    {tab}List of directory globs to be excluded
+{tab}<bold>_ZO_FUZZY</bold>           {tab}Match keywords fuzzily when set to 1
 {tab}<bold>_ZO_FZF_OPTS</bold>        {tab}Custom flags to pass to fzf
 {tab}<bold>_ZO_MAXAGE</bold>          {tab}Maximum total age after which entries start getting deleted
-{tab}<bold>_ZO_RESOLVE_SYMLINKS</bold>{tab}Resolve symlinks when storing paths").into_resettable()
+{tab}<bold>_ZO_PICKER</bold>          {tab}Interactive picker to use (fzf, builtin, sk, fzy, peco, custom)
+{tab}<bold>_ZO_PICKER_COMMAND</bold>  {tab}Command to run for the custom picker
+{tab}<bold>_ZO_PICKER_FRAMING</bold>  {tab}Input/output framing of the custom picker (read0, print0, scores)
+{tab}<bold>_ZO_PREVIEW</bold>         {tab}Preview in the interactive picker (ls, eza, lsd, tree, git, zoxide, none, or a command)
+{tab}<bold>_ZO_PREVIEW_WINDOW</bold>  {tab}Layout of the preview window, as passed to fzf's --preview-window
+{tab}<bold>_ZO_PROXIMITY</bold>       {tab}How much to favor directories near the current one (default 0, disabled)
+{tab}<bold>_ZO_RESOLVE_SYMLINKS</bold>{tab}Resolve symlinks when storing paths
+{tab}<bold>_ZO_SMART_CASE</bold>      {tab}Match keywords with uppercase letters case-sensitively when set to 1").into_resettable()
     }
 }
 
@@ -42,11 +50,17 @@ https://github.com/ajeetdsouza/zoxide
 )]
 pub enum Cmd {
     Add(Add),
+    Alias(Alias),
     Edit(Edit),
+    Export(Export),
     Import(Import),
     Init(Init),
+    Migrate(Migrate),
+    Mv(Mv),
+    Preview(Preview),
     Query(Query),
     Remove(Remove),
+    Stats(Stats),
 }
 
 /// Add a new directory or increment its rank
@@ -56,15 +70,48 @@ pub enum Cmd {
     help_template = HelpTemplate,
 )]
 pub struct Add {
-    #[clap(num_args = 1.., required = true, value_hint = ValueHint::DirPath)]
+    #[clap(num_args = 1.., required_unless_present = "stdin0", value_hint = ValueHint::DirPath)]
     pub paths: Vec<PathBuf>,
 
+    /// Also read NUL-separated paths from stdin, allowing paths that contain
+    /// newlines
+    #[clap(long)]
+    pub stdin0: bool,
+
     /// The rank to increment the entry if it exists or initialize it with if it
     /// doesn't
     #[clap(short, long)]
     pub score: Option<f64>,
 }
 
+/// Manage named aliases for directories
+///
+/// An alias can be used to jump directly to its directory with `z @name`,
+/// bypassing keyword matching.
+#[derive(Debug, Parser)]
+#[clap(
+    author,
+    help_template = HelpTemplate,
+)]
+pub struct Alias {
+    #[clap(subcommand)]
+    pub cmd: AliasCommand,
+}
+
+#[derive(Clone, Debug, Subcommand)]
+pub enum AliasCommand {
+    /// Create an alias, or change the directory it points to
+    Add {
+        name: String,
+        #[clap(value_hint = ValueHint::DirPath)]
+        path: PathBuf,
+    },
+    /// List all aliases
+    List,
+    /// Remove an alias
+    Remove { name: String },
+}
+
 /// Edit the database
 #[derive(Debug, Parser)]
 #[clap(
@@ -85,7 +132,39 @@ pub enum EditCommand {
     #[clap(hide = true)]
     Increment { path: String },
     #[clap(hide = true)]
+    Pin { path: String },
+    #[clap(hide = true)]
     Reload,
+    #[clap(hide = true)]
+    Unpin { path: String },
+}
+
+/// Export the database to another format
+#[derive(Debug, Parser)]
+#[clap(
+    author,
+    help_template = HelpTemplate,
+)]
+pub struct Export {
+    /// Format to write the entries in
+    #[clap(value_enum, long, short, default_value = "json")]
+    pub format: ExportFormat,
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ExportFormat {
+    /// autojump's data file (`rank<TAB>path`)
+    Autojump,
+    /// CSV, with a header row
+    Csv,
+    /// A JSON array of entries
+    Json,
+    /// One JSON entry per line
+    Jsonl,
+    /// z's data file (`path|rank|last_accessed`), also used by fasd, z.lua
+    /// and zsh-z
+    #[clap(aliases = ["fasd", "z.lua", "zsh-z"])]
+    Z,
 }
 
 /// Import entries from another application
@@ -111,6 +190,16 @@ pub enum ImportFrom {
     Autojump,
     /// Import from fasd
     Fasd,
+    /// Import from a JSON array or JSON-lines file, as written by `zoxide
+    /// export`
+    ///
+    /// Each entry is an object with the keys `path`, `rank` and
+    /// `last_accessed`, and optionally `pinned`.
+    Json {
+        /// File to read from, or `-` to read from stdin
+        #[clap(value_hint = ValueHint::FilePath)]
+        path: PathBuf,
+    },
     /// Import from z
     Z,
     /// Import from z.lua
@@ -165,11 +254,50 @@ pub enum InitShell {
     Zsh,
 }
 
+/// Convert the database to another format version
+#[derive(Debug, Parser)]
+#[clap(
+    author,
+    help_template = HelpTemplate,
+)]
+pub struct Migrate {
+    /// The version to convert to (defaults to the latest)
+    ///
+    /// Older versions can be used to hand the database back to an older
+    /// release of zoxide. Any data that the older version can't represent is
+    /// discarded. Running a newer release afterwards upgrades the database
+    /// again.
+    #[clap(long, value_name = "version")]
+    pub to: Option<u32>,
+}
+
+/// Update the database after moving a directory
+///
+/// Every entry under the old path is moved to the same relative path under the
+/// new one. Entries that end up with the same path are merged.
+#[derive(Debug, Parser)]
+#[clap(
+    author,
+    help_template = HelpTemplate,
+)]
+pub struct Mv {
+    #[clap(value_hint = ValueHint::DirPath)]
+    pub from: PathBuf,
+
+    #[clap(value_hint = ValueHint::DirPath)]
+    pub to: PathBuf,
+
+    /// List the entries that would be moved, without changing the database
+    #[clap(long, short = 'n')]
+    pub dry_run: bool,
+}
+
 /// Search for a directory in the database
 #[derive(Debug, Parser)]
 #[clap(
     author,
     help_template = HelpTemplate,
+    group = ArgGroup::new("results").args(["list", "multi"]),
 )]
 pub struct Query {
     pub keywords: Vec<String>,
@@ -186,10 +314,45 @@ pub struct Query {
     #[clap(long, short, conflicts_with = "interactive")]
     pub list: bool,
 
+    /// Select several directories, then print them with Enter, remove them
+    /// from the database with Ctrl-D, or open them with Ctrl-O
+    #[clap(long, short, requires = "interactive")]
+    pub multi: bool,
+
+    /// Explain how each listed directory was ranked, and why any were
+    /// filtered out
+    #[clap(long, requires = "list", conflicts_with = "format")]
+    pub explain: bool,
+
+    /// Format to print results in
+    #[clap(value_enum, long, default_value = "text")]
+    pub format: QueryFormat,
+
+    /// Separate listed or selected directories with NUL instead of newline
+    #[clap(long, short = '0', requires = "results", conflicts_with_all = ["explain", "format"])]
+    pub print0: bool,
+
+    /// Search the parents of the current directory first, returning the
+    /// nearest one that matches
+    #[clap(long, conflicts_with_all = ["interactive", "list"])]
+    pub parent: bool,
+
     /// Print score with results
     #[clap(long, short)]
     pub score: bool,
 
+    /// Match keywords fuzzily, and rank results by how well they match
+    #[clap(long, conflicts_with = "regex")]
+    pub fuzzy: bool,
+
+    /// Treat keywords as regular expressions
+    #[clap(long)]
+    pub regex: bool,
+
+    /// Match keywords case-sensitively if they contain uppercase letters
+    #[clap(long)]
+    pub smart_case: bool,
+
     /// Exclude the current directory
     #[clap(long, value_hint = ValueHint::DirPath, value_name = "path")]
     pub exclude: Option<String>,
@@ -199,6 +362,27 @@ pub struct Query {
     pub base_dir: Option<String>,
 }
 
+#[derive(ValueEnum, Clone, Copy, Debug, Eq, PartialEq)]
+pub enum QueryFormat {
+    /// Paths, with scores if `--score` is given
+    Text,
+    /// A JSON object, or an array of them with `--list`
+    Json,
+    /// One JSON object per line
+    Jsonl,
+}
+
+/// Show the rank, last access time, and contents of a directory
+#[derive(Debug, Parser)]
+#[clap(
+    author,
+    help_template = HelpTemplate,
+)]
+pub struct Preview {
+    #[clap(value_hint = ValueHint::DirPath)]
+    pub path: String,
+}
+
 /// Remove a directory from the database
 #[derive(Debug, Parser)]
 #[clap(
@@ -208,4 +392,32 @@ pub struct Query {
 pub struct Remove {
     #[clap(value_hint = ValueHint::DirPath)]
     pub paths: Vec<String>,
+
+    /// Prevent the directories from being added again (paths may be globs)
+    #[clap(long, conflicts_with_all = ["forgotten", "unforget"])]
+    pub forget: bool,
+
+    /// List directories that have been forgotten
+    #[clap(long, conflicts_with = "unforget")]
+    pub forgotten: bool,
+
+    /// Allow forgotten directories to be added again
+    #[clap(long)]
+    pub unforget: bool,
+
+    /// Also read NUL-separated paths from stdin
+    #[clap(long, conflicts_with = "forgotten")]
+    pub stdin0: bool,
+}
+
+/// Show a summary of the database
+#[derive(Debug, Parser)]
+#[clap(
+    author,
+    help_template = HelpTemplate,
+)]
+pub struct Stats {
+    /// Number of directories to show in each top list
+    #[clap(long, short = 'n', value_name = "count", default_value_t = 10)]
+    pub top: usize,
 }
@@ -5,36 +5,51 @@ use anyhow::Result;
 use crate::cmd::{Edit, EditCommand, Run};
 use crate::db::Database;
 use crate::error::BrokenPipeHandler;
-use crate::util::{self, Fzf, FzfChild};
+use crate::util::{self, FzfVersion, Picker, PickerChild};
 
 impl Run for Edit {
     fn run(&self) -> Result<()> {
-        let now = util::current_time()?;
-        let db = &mut Database::open()?;
-
         match &self.cmd {
             Some(cmd) => {
+                let now = util::current_time()?;
+                let db = &mut Database::open()?;
                 match cmd {
                     EditCommand::Decrement { path } => db.add(path, -1.0, now),
                     EditCommand::Delete { path } => {
                         db.remove(path);
                     }
                     EditCommand::Increment { path } => db.add(path, 1.0, now),
+                    EditCommand::Pin { path } => {
+                        db.set_pinned(path, true);
+                    }
                     EditCommand::Reload => {}
+                    EditCommand::Unpin { path } => {
+                        db.set_pinned(path, false);
+                    }
                 }
                 db.save()?;
 
                 let stdout = &mut io::stdout().lock();
                 for dir in db.dirs().iter().rev() {
-                    write!(stdout, "{}\0", dir.display().with_score(now).with_separator('\t'))
-                        .pipe_exit("fzf")?;
+                    write!(
+                        stdout,
+                        "{}\0",
+                        dir.display().with_score(dir.score(now)).with_separator('\t')
+                    )
+                    .pipe_exit("fzf")?;
                 }
                 Ok(())
             }
             None => {
+                // Persist the sorted order, so that entries don't move around as
+                // they are edited from within fzf.
+                let now = util::current_time()?;
+                let db = &mut Database::open()?;
                 db.sort_by_score(now);
+                db.mark_dirty();
                 db.save()?;
-                Self::get_fzf()?.wait()?;
+
+                Self::get_picker()?.wait()?;
                 Ok(())
             }
         }
@@ -42,8 +57,18 @@ impl Run for Edit {
 }
 
 impl Edit {
-    fn get_fzf() -> Result<FzfChild> {
-        Fzf::new()?
+    const HEADER: &str = "\
+ctrl-r:reload   \tctrl-d:delete
+ctrl-w:increment\tctrl-s:decrement
+ctrl-p:pin      \tctrl-u:unpin
+
+ SCORE\tPATH";
+
+    fn get_picker() -> Result<PickerChild> {
+        Picker::new()?
+            .fzf_or_builtin()
+            // Entries are loaded using the `start` event.
+            .min_version(FzfVersion(0, 28, 0), "zoxide edit")
             .args([
                 // Search mode
                 "--exact",
@@ -54,6 +79,8 @@ impl Edit {
 btab:up,\
 ctrl-r:reload(zoxide edit reload),\
 ctrl-d:reload(zoxide edit delete {2..}),\
+ctrl-p:reload(zoxide edit pin {2..}),\
+ctrl-u:reload(zoxide edit unpin {2..}),\
 ctrl-w:reload(zoxide edit increment {2..}),\
 ctrl-s:reload(zoxide edit decrement {2..}),\
 ctrl-z:ignore,\
@@ -66,11 +93,7 @@ tab:down",
                 // Layout
                 "--border=sharp",
                 "--border-label=  zoxide-edit  ",
-                "--header=\
-ctrl-r:reload   \tctrl-d:delete
-ctrl-w:increment\tctrl-s:decrement
-
- SCORE\tPATH",
+                &format!("--header={}", Self::HEADER),
                 "--info=inline",
                 "--layout=reverse",
                 "--padding=1,0,0,0",
@@ -79,6 +102,18 @@ ctrl-w:increment\tctrl-s:decrement
                 "--tabstop=1",
             ])
             .enable_preview()
+            .builtin(|picker| {
+                picker
+                    .header(Self::HEADER)
+                    .no_accept()
+                    .start(["edit", "reload"])
+                    .bind('r', ["edit", "reload"])
+                    .bind('d', ["edit", "delete", "{}"])
+                    .bind('p', ["edit", "pin", "{}"])
+                    .bind('u', ["edit", "unpin", "{}"])
+                    .bind('w', ["edit", "increment", "{}"])
+                    .bind('s', ["edit", "decrement", "{}"]);
+            })
             .spawn()
     }
 }
@@ -0,0 +1,159 @@
+use std::io::{self, Write};
+
+use anyhow::Result;
+
+use crate::cmd::{Export, ExportFormat, Run};
+use crate::db::{Database, Dir, DirRecord};
+use crate::error::BrokenPipeHandler;
+use crate::util;
+
+impl Run for Export {
+    fn run(&self) -> Result<()> {
+        let now = util::current_time()?;
+        let mut db = Database::open()?;
+        db.sort_by_score(now);
+        db.save()?;
+
+        let dirs = db.dirs().iter().rev();
+        let skipped = write(&mut io::stdout().lock(), self.format, dirs)?;
+        if !skipped.is_empty() {
+            let stderr = &mut io::stderr().lock();
+            for path in skipped {
+                _ = writeln!(stderr, "zoxide: skipped path that can't be exported: {path:?}");
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Writes `dirs` in the given format. Returns the paths that had to be left
+/// out, because the format can't represent them.
+fn write<'a>(
+    handle: &mut impl Write,
+    format: ExportFormat,
+    dirs: impl Iterator<Item = &'a Dir<'a>>,
+) -> Result<Vec<&'a str>> {
+    let mut skipped = Vec::new();
+    match format {
+        ExportFormat::Json => {
+            let records = dirs.map(DirRecord::from).collect::<Vec<_>>();
+            serde_json::to_writer_pretty(&mut *handle, &records)
+                .map_err(io::Error::from)
+                .pipe_exit("stdout")?;
+            writeln!(handle).pipe_exit("stdout")?;
+        }
+        ExportFormat::Jsonl => {
+            for dir in dirs {
+                serde_json::to_writer(&mut *handle, &DirRecord::from(dir))
+                    .map_err(io::Error::from)
+                    .pipe_exit("stdout")?;
+                writeln!(handle).pipe_exit("stdout")?;
+            }
+        }
+        ExportFormat::Csv => {
+            writeln!(handle, "path,rank,last_accessed,pinned").pipe_exit("stdout")?;
+            for dir in dirs {
+                writeln!(
+                    handle,
+                    "{},{},{},{}",
+                    csv_escape(&dir.path),
+                    dir.rank,
+                    dir.last_accessed,
+                    dir.pinned
+                )
+                .pipe_exit("stdout")?;
+            }
+        }
+        ExportFormat::Autojump => {
+            for dir in dirs {
+                // Entries are separated by newlines.
+                if dir.path.contains(['\n', '\r']) {
+                    skipped.push(dir.path.as_ref());
+                    continue;
+                }
+                writeln!(handle, "{}\t{}", dir.rank, dir.path).pipe_exit("stdout")?;
+            }
+        }
+        ExportFormat::Z => {
+            for dir in dirs {
+                // Entries are separated by newlines, and fields by `|`.
+                if dir.path.contains(['\n', '\r', '|']) {
+                    skipped.push(dir.path.as_ref());
+                    continue;
+                }
+                writeln!(handle, "{}|{}|{}", dir.path, dir.rank, dir.last_accessed)
+                    .pipe_exit("stdout")?;
+            }
+        }
+    }
+    Ok(skipped)
+}
+
+/// Quotes a CSV field if it contains any special characters.
+fn csv_escape(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("/foo/bar", "/foo/bar")]
+    #[case("", "")]
+    #[case("/foo,bar", "\"/foo,bar\"")]
+    #[case("/foo\"bar\"", "\"/foo\"\"bar\"\"\"")]
+    #[case("/foo\nbar", "\"/foo\nbar\"")]
+    #[case("/foo\rbar", "\"/foo\rbar\"")]
+    fn csv_escape(#[case] field: &str, #[case] expected: &str) {
+        assert_eq!(super::csv_escape(field), expected);
+    }
+
+    fn dirs() -> Vec<Dir<'static>> {
+        ["/foo", "/foo|bar", "/foo\nbar", "/foo,bar"]
+            .into_iter()
+            .map(|path| Dir { path: path.into(), rank: 1.5, last_accessed: 100, pinned: false })
+            .collect()
+    }
+
+    #[rstest]
+    #[case(ExportFormat::Autojump, "1.5\t/foo\n1.5\t/foo|bar\n1.5\t/foo,bar\n", &["/foo\nbar"])]
+    #[case(ExportFormat::Z, "/foo|1.5|100\n/foo,bar|1.5|100\n", &["/foo|bar", "/foo\nbar"])]
+    #[case(
+        ExportFormat::Csv,
+        "path,rank,last_accessed,pinned\n/foo,1.5,100,false\n/foo|bar,1.5,100,false\n\
+         \"/foo\nbar\",1.5,100,false\n\"/foo,bar\",1.5,100,false\n",
+        &[]
+    )]
+    fn write(#[case] format: ExportFormat, #[case] expected: &str, #[case] skipped: &[&str]) {
+        let dirs = dirs();
+        let mut output = Vec::new();
+        assert_eq!(super::write(&mut output, format, dirs.iter()).unwrap(), skipped);
+        assert_eq!(String::from_utf8(output).unwrap(), expected);
+    }
+
+    #[test]
+    fn write_json() {
+        let dirs = dirs();
+        let mut output = Vec::new();
+        assert!(super::write(&mut output, ExportFormat::Json, dirs.iter()).unwrap().is_empty());
+        let records = serde_json::from_slice::<Vec<DirRecord>>(&output).unwrap();
+        let paths = records.iter().map(|record| record.path.as_ref()).collect::<Vec<_>>();
+        assert_eq!(paths, ["/foo", "/foo|bar", "/foo\nbar", "/foo,bar"]);
+
+        let mut output = Vec::new();
+        assert!(super::write(&mut output, ExportFormat::Jsonl, dirs.iter()).unwrap().is_empty());
+        let output = String::from_utf8(output).unwrap();
+        assert_eq!(output.lines().count(), dirs.len());
+        for (line, dir) in output.lines().zip(&dirs) {
+            let record = serde_json::from_str::<DirRecord>(line).unwrap();
+            assert_eq!(record.path, dir.path);
+        }
+    }
+}
@@ -11,10 +11,13 @@ impl Run for Import {
             bail!("current database is not empty, specify --merge to continue anyway");
         }
 
-        match self.from {
+        match &self.from {
             ImportFrom::Atuin => import::run(&import::Atuin {}, &mut db)?,
             ImportFrom::Autojump => import::run(&import::Autojump {}, &mut db)?,
             ImportFrom::Fasd => import::run(&import::Fasd {}, &mut db)?,
+            ImportFrom::Json { path } => {
+                import::run(&import::Json { path: path.clone() }, &mut db)?
+            }
             ImportFrom::Z => import::run(&import::Z {}, &mut db)?,
             ImportFrom::ZLua => import::run(&import::ZLua {}, &mut db)?,
             ImportFrom::ZshZ => import::run(&import::ZshZ {}, &mut db)?,
@@ -0,0 +1,27 @@
+use std::io::{self, Write};
+
+use anyhow::{Result, ensure};
+
+use crate::cmd::{Migrate, Run};
+use crate::db::{self, Database};
+
+impl Run for Migrate {
+    fn run(&self) -> Result<()> {
+        let version = self.to.unwrap_or(db::VERSION);
+        ensure!(
+            (db::VERSION_MIN..=db::VERSION).contains(&version),
+            "unsupported version (got {version}, supports {} to {})",
+            db::VERSION_MIN,
+            db::VERSION
+        );
+
+        let mut db = Database::open()?;
+        let dropped = db.save_version(version)?;
+
+        let stderr = &mut io::stderr().lock();
+        for name in dropped {
+            _ = writeln!(stderr, "zoxide: discarded data unsupported by version {version}: {name}");
+        }
+        Ok(())
+    }
+}
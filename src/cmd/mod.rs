@@ -1,10 +1,16 @@
 mod add;
+mod alias;
 mod cmd;
 mod edit;
+mod export;
 mod import;
 mod init;
+mod migrate;
+mod mv;
+mod preview;
 mod query;
 mod remove;
+mod stats;
 
 use anyhow::Result;
 
@@ -18,11 +24,17 @@ impl Run for Cmd {
     fn run(&self) -> Result<()> {
         match self {
             Cmd::Add(cmd) => cmd.run(),
+            Cmd::Alias(cmd) => cmd.run(),
             Cmd::Edit(cmd) => cmd.run(),
+            Cmd::Export(cmd) => cmd.run(),
             Cmd::Import(cmd) => cmd.run(),
             Cmd::Init(cmd) => cmd.run(),
+            Cmd::Migrate(cmd) => cmd.run(),
+            Cmd::Mv(cmd) => cmd.run(),
+            Cmd::Preview(cmd) => cmd.run(),
             Cmd::Query(cmd) => cmd.run(),
             Cmd::Remove(cmd) => cmd.run(),
+            Cmd::Stats(cmd) => cmd.run(),
         }
     }
 }
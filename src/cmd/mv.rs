@@ -0,0 +1,51 @@
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, bail};
+
+use crate::cmd::{Mv, Run};
+use crate::db::Database;
+use crate::error::BrokenPipeHandler;
+use crate::{config, util};
+
+impl Run for Mv {
+    fn run(&self) -> Result<()> {
+        let from = resolve(&self.from)?;
+        let to = resolve(&self.to)?;
+
+        let mut db = Database::open()?;
+        let moved = db.move_subtree(&from, &to);
+        if moved.is_empty() {
+            bail!("path not found in database: {}", from.display());
+        }
+
+        if self.dry_run {
+            let handle = &mut io::stdout().lock();
+            for (from, to) in moved {
+                writeln!(handle, "{from} -> {to}").pipe_exit("stdout")?;
+            }
+            // Drop the changes.
+            return Ok(());
+        }
+        db.save()
+    }
+}
+
+/// Resolves a path like `zoxide add` does. The old path usually doesn't exist
+/// anymore, so if symlinks are resolved, only its parent is canonicalized.
+fn resolve(path: &Path) -> Result<PathBuf> {
+    let path = util::resolve_path(path)?;
+    if !config::resolve_symlinks() {
+        return Ok(path);
+    }
+    if let Ok(path) = util::canonicalize(&path) {
+        return Ok(path);
+    }
+    let path = match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => {
+            util::canonicalize(parent).map_or(path.clone(), |parent| parent.join(name))
+        }
+        _ => path,
+    };
+    Ok(path)
+}
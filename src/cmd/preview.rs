@@ -0,0 +1,55 @@
+use std::env;
+use std::fmt::Write as _;
+use std::io::{self, Write};
+use std::path::Path;
+
+use anyhow::Result;
+
+use crate::cmd::{Preview, Run};
+use crate::db::Database;
+use crate::error::BrokenPipeHandler;
+use crate::{picker, util};
+
+impl Run for Preview {
+    fn run(&self) -> Result<()> {
+        let now = util::current_time()?;
+        let db = Database::open_read_only()?;
+
+        // Paths from the picker are already in the database, others are
+        // resolved the same way `zoxide add` would.
+        let mut path = self.path.clone();
+        if !db.dirs().iter().any(|dir| dir.path == path)
+            && let Ok(path_abs) = util::resolve_path(&path)
+        {
+            path = util::path_to_str(&path_abs)?.to_string();
+        }
+
+        let mut out = String::new();
+        _ = writeln!(out, "{path}");
+        match db.dirs().iter().find(|dir| dir.path == path) {
+            Some(dir) => {
+                let pinned = if dir.pinned { " (pinned)" } else { "" };
+                let ago = util::format_duration(now.saturating_sub(dir.last_accessed));
+                _ = writeln!(out, "Rank            {:.1}{pinned}", dir.rank);
+                _ = writeln!(out, "Last accessed   {ago} ago");
+            }
+            None => _ = writeln!(out, "Not in the database"),
+        }
+        _ = writeln!(out);
+        for line in picker::list_dir(Path::new(&path), width()) {
+            _ = writeln!(out, "{line}");
+        }
+
+        io::stdout().write_all(out.as_bytes()).pipe_exit("stdout")
+    }
+}
+
+/// Returns the width of the preview window, which fzf sets for the preview
+/// command, or of the terminal otherwise.
+fn width() -> usize {
+    ["FZF_PREVIEW_COLUMNS", "COLUMNS"]
+        .into_iter()
+        .find_map(|var| env::var(var).ok()?.parse().ok())
+        .or_else(|| crossterm::terminal::size().ok().map(|(width, _)| width as usize))
+        .unwrap_or(80)
+}
@@ -1,12 +1,21 @@
+use std::collections::HashMap;
+use std::fs;
 use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, ensure};
+use glob::{MatchOptions, Pattern};
+use serde::Serialize;
 
-use crate::cmd::{Query, Run};
+use crate::cmd::{Query, QueryFormat, Run};
 use crate::config;
-use crate::db::{Database, Epoch, Stream, StreamOptions};
+use crate::db::{
+    Database, Dir, DirRecord, Epoch, Filtered, Keywords, Matching, Proximity, Rank, Stream,
+    StreamOptions,
+};
 use crate::error::BrokenPipeHandler;
-use crate::util::{self, Fzf, FzfChild};
+use crate::util::{self, Picker, PickerChild};
 
 impl Run for Query {
     fn run(&self) -> Result<()> {
@@ -16,106 +25,552 @@ impl Run for Query {
 }
 
 impl Query {
+    /// Ctrl+keys that act on the directories selected with `--multi`.
+    const KEY_REMOVE: char = 'd';
+    const KEY_OPEN: char = 'o';
+    const MULTI_HEADER: &str = "enter:print\tctrl-d:remove\tctrl-o:open";
+
     fn query(&self, db: &mut Database) -> Result<()> {
         let now = util::current_time()?;
-        let mut stream = self.get_stream(db, now)?;
-
+        if let Some(path) = self.get_alias(db) {
+            return self.print_path(path.to_string(), now);
+        }
         if self.interactive {
-            self.query_interactive(&mut stream, now)
+            return self.query_interactive(db, now);
+        }
+
+        if self.parent
+            && let Some(path) = self.query_parent(db, now)?
+        {
+            return self.print_path(path, now);
+        }
+
+        let mut stream = self.get_stream(db, &self.keywords, now)?;
+        if self.list && self.explain {
+            self.query_explain(&mut stream)
         } else if self.list {
-            self.query_list(&mut stream, now)
+            self.query_list(&mut stream)
+        } else if let Some(path) = self.query_first(&mut stream)? {
+            db.learn(&self.keywords()?, path, now);
+            Ok(())
         } else {
-            self.query_first(&mut stream, now)
+            let (dir, score) = self.query_subdir(db, now)?.context("no match found")?;
+            self.write_dir(&mut io::stdout(), &dir, score, true)
         }
     }
 
-    fn query_interactive(&self, stream: &mut Stream, now: Epoch) -> Result<()> {
-        let mut fzf = Self::get_fzf()?;
-        let selection = loop {
-            match stream.next() {
-                Some(dir) if Some(dir.path.as_ref()) == self.exclude.as_deref() => continue,
-                Some(dir) => {
-                    if let Some(selection) = fzf.write(dir, now)? {
-                        break selection;
+    fn query_interactive(&self, db: &mut Database, now: Epoch) -> Result<()> {
+        let mut picker = self.get_picker()?;
+        let aliases = db.aliases().to_vec();
+        // Remember what was shown to the user, for structured output.
+        let mut records = HashMap::new();
+        let selection = 'selection: {
+            let mut stream = self.get_stream(db, &self.keywords, now)?;
+            for alias in &aliases {
+                if Some(alias.path.as_str()) == self.exclude.as_deref()
+                    || !stream.filter_by_keywords(&alias.path)
+                {
+                    continue;
+                }
+                if let Some(selection) = picker.write_alias(alias)? {
+                    break 'selection Some(selection);
+                }
+            }
+            loop {
+                match stream.next() {
+                    Some((dir, _)) if Some(dir.path.as_ref()) == self.exclude.as_deref() => {
+                        continue;
+                    }
+                    Some((dir, score)) => {
+                        if self.format != QueryFormat::Text {
+                            let record = Record::new(dir, score, self.exists(dir));
+                            records.insert(dir.path.to_string(), record);
+                        }
+                        if let Some(selection) = picker.write(dir, score)? {
+                            break Some(selection);
+                        }
                     }
+                    None => break None,
                 }
-                None => break fzf.wait()?,
             }
         };
+        // Release the database before waiting on the user, so that other shells
+        // aren't blocked while the picker is open. It is opened again below if
+        // the selection changes it.
+        db.save()?;
+        let selection = match selection {
+            Some(selection) => selection,
+            None => picker.wait()?,
+        };
 
-        if self.score {
-            print!("{selection}");
-        } else {
-            let path = selection.get(7..).context("could not read selection from fzf")?;
-            print!("{path}");
+        let paths = selection
+            .entries
+            .iter()
+            .map(|entry| {
+                let (_, path) = entry.split_once('\t')?;
+                Some(path)
+            })
+            .collect::<Option<Vec<_>>>()
+            .context("could not read selection from picker")?;
+        match selection.key {
+            Some(Self::KEY_REMOVE) => {
+                if paths.len() > 1 && !confirm(&format!("remove {} directories?", paths.len()))? {
+                    return Ok(());
+                }
+                *db = Database::open()?;
+                for path in &paths {
+                    db.remove(path);
+                }
+                return Ok(());
+            }
+            Some(Self::KEY_OPEN) => return paths.iter().try_for_each(|path| open_dir(path)),
+            _ => {}
+        }
+
+        let handle = &mut io::stdout().lock();
+        let terminator = if self.print0 { '\0' } else { '\n' };
+        let mut json = Vec::new();
+        for (entry, path) in selection.entries.iter().zip(&paths) {
+            match self.format {
+                QueryFormat::Text => {
+                    let line = if self.score { entry.as_str() } else { path };
+                    write!(handle, "{line}{terminator}").pipe_exit("stdout")?;
+                }
+                _ => {
+                    // Aliases aren't directories in the database, so they have
+                    // no score, and haven't been checked for existence.
+                    let record = records.remove(*path).unwrap_or_else(|| {
+                        let dir = Dir {
+                            path: (*path).into(),
+                            rank: 0.0,
+                            last_accessed: now,
+                            pinned: false,
+                        };
+                        Record::new(&dir, 0.0, Path::new(path).is_dir())
+                    });
+                    if self.multi && self.format == QueryFormat::Json {
+                        json.push(record);
+                    } else {
+                        self.write_record(handle, &record)?;
+                    }
+                }
+            }
+        }
+        if self.multi && self.format == QueryFormat::Json {
+            write_records(handle, &json)?;
+        }
+
+        // Choosing several directories doesn't say much about any one of them.
+        if !self.multi && !self.keywords.is_empty() {
+            *db = Database::open()?;
+            db.learn(&self.keywords()?, paths[0], now);
         }
         Ok(())
     }
 
-    fn query_list(&self, stream: &mut Stream, now: Epoch) -> Result<()> {
+    fn query_list(&self, stream: &mut Stream) -> Result<()> {
         let handle = &mut io::stdout().lock();
-        while let Some(dir) = stream.next() {
+        let mut records = Vec::new();
+        while let Some((dir, score)) = stream.next() {
             if Some(dir.path.as_ref()) == self.exclude.as_deref() {
                 continue;
             }
-            let dir = if self.score { dir.display().with_score(now) } else { dir.display() };
-            writeln!(handle, "{dir}").pipe_exit("stdout")?;
+            match self.format {
+                QueryFormat::Text => self.write_dir(handle, dir, score, true)?,
+                QueryFormat::Json => records.push(Record::new(dir, score, self.exists(dir))),
+                QueryFormat::Jsonl => {
+                    self.write_record(handle, &Record::new(dir, score, self.exists(dir)))?;
+                }
+            }
+        }
+        if self.format == QueryFormat::Json {
+            write_records(handle, &records)?;
         }
         Ok(())
     }
 
-    fn query_first(&self, stream: &mut Stream, now: Epoch) -> Result<()> {
+    fn query_explain(&self, stream: &mut Stream) -> Result<()> {
+        let handle = &mut io::stdout().lock();
+        while let Some(explanation) = stream.next_explained() {
+            let dir = &explanation.dir;
+            writeln!(handle, "{}", dir.display().with_score(explanation.score))
+                .pipe_exit("stdout")?;
+
+            let mut reasons = vec![format!(
+                "rank {:.1}, accessed {} (x{})",
+                dir.rank, explanation.age, explanation.age_factor
+            )];
+            if dir.pinned {
+                reasons.push("pinned, so ranked above unpinned directories".to_string());
+            }
+            for (keyword, range) in self.keywords.iter().zip(&explanation.matches) {
+                let (path, range) = (&dir.path, range.clone());
+                let (before, matched, after) =
+                    (&path[..range.start], &path[range.clone()], &path[range.end..]);
+                reasons.push(format!("{keyword:?} matched {before}[{matched}]{after}"));
+            }
+            if self.matching() == Matching::Fuzzy {
+                reasons.push(format!("matched fuzzily (x{:.2})", explanation.quality));
+            }
+            if explanation.learned != 1.0 {
+                reasons.push(format!("learned from previous jumps (x{:.2})", explanation.learned));
+            }
+            if explanation.proximity != 1.0 {
+                reasons.push(format!("near the current directory (x{:.2})", explanation.proximity));
+            }
+            let filtered = match explanation.filtered {
+                _ if Some(dir.path.as_ref()) == self.exclude.as_deref() => {
+                    Some("it is the current directory")
+                }
+                Some(Filtered::BaseDir) => Some("it is outside the base directory"),
+                Some(Filtered::Exclude) => Some("it is excluded by _ZO_EXCLUDE_DIRS, removed"),
+                Some(Filtered::Missing) => Some("it does not exist"),
+                Some(Filtered::Pruned) => {
+                    Some("it does not exist and has not been accessed recently, removed")
+                }
+                None => None,
+            };
+            if let Some(filtered) = filtered {
+                reasons.push(format!("filtered out: {filtered}"));
+            }
+
+            for reason in reasons {
+                writeln!(handle, "{:8}{reason}", "").pipe_exit("stdout")?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Prints the best match, and returns its path. Returns `None` if nothing
+    /// matched.
+    fn query_first(&self, stream: &mut Stream) -> Result<Option<String>> {
         let handle = &mut io::stdout();
 
-        let mut dir = stream.next().context("no match found")?;
+        let Some((mut dir, mut score)) = stream.next() else {
+            return Ok(None);
+        };
         while Some(dir.path.as_ref()) == self.exclude.as_deref() {
-            dir = stream.next().context("you are already in the only match")?;
+            (dir, score) = stream.next().context("you are already in the only match")?;
         }
 
-        let dir = if self.score { dir.display().with_score(now) } else { dir.display() };
-        writeln!(handle, "{dir}").pipe_exit("stdout")
+        self.write_dir(handle, dir, score, self.exists(dir))?;
+        Ok(Some(dir.path.to_string()))
     }
 
-    fn get_stream<'a>(&self, db: &'a mut Database, now: Epoch) -> Result<Stream<'a>> {
+    /// Returns the nearest parent of the current directory that matches the
+    /// keywords. Parents are taken from `$PWD` where possible, so that `z ..`
+    /// goes back up through symlinks like `cd ..` does.
+    fn query_parent(&self, db: &mut Database, now: Epoch) -> Result<Option<String>> {
+        let stream = self.get_stream(db, &self.keywords, now)?;
+        let current_dir = util::resolve_path(util::logical_current_dir()?)?;
+        for path in current_dir.ancestors().skip(1) {
+            let path = util::path_to_str(&path)?;
+            if Some(path) != self.exclude.as_deref() && stream.filter_by_keywords(path) {
+                return Ok(Some(path.to_string()));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Prints a path that was found outside the database, and so has no score.
+    fn print_path(&self, path: String, now: Epoch) -> Result<()> {
+        let exists = Path::new(&path).is_dir();
+        let dir = Dir { path: path.into(), rank: 0.0, last_accessed: now, pinned: false };
+        self.write_dir(&mut io::stdout(), &dir, 0.0, exists)
+    }
+
+    /// Whether a directory from the stream exists. Unless `--all` is given,
+    /// the stream has already filtered out those that don't.
+    fn exists(&self, dir: &Dir) -> bool {
+        !self.all || Path::new(dir.path.as_ref()).is_dir()
+    }
+
+    /// Writes a single result in the requested format.
+    fn write_dir(
+        &self,
+        handle: &mut impl Write,
+        dir: &Dir,
+        score: Rank,
+        exists: bool,
+    ) -> Result<()> {
+        match self.format {
+            QueryFormat::Text => {
+                let dir = if self.score { dir.display().with_score(score) } else { dir.display() };
+                let terminator = if self.print0 { '\0' } else { '\n' };
+                write!(handle, "{dir}{terminator}").pipe_exit("stdout")
+            }
+            QueryFormat::Json | QueryFormat::Jsonl => {
+                self.write_record(handle, &Record::new(dir, score, exists))
+            }
+        }
+    }
+
+    fn write_record(&self, handle: &mut impl Write, record: &Record) -> Result<()> {
+        let result = if self.format == QueryFormat::Json {
+            serde_json::to_writer_pretty(&mut *handle, record)
+        } else {
+            serde_json::to_writer(&mut *handle, record)
+        };
+        result.map_err(io::Error::from).pipe_exit("stdout")?;
+        writeln!(handle).pipe_exit("stdout")
+    }
+
+    /// Used when no directory in the database matches. Matches the leading
+    /// keywords against the database, and looks for the trailing keywords as a
+    /// relative path (or glob) beneath the best match, e.g. `z proj src` finds
+    /// `~/work/proj/src` as long as `~/work/proj` is in the database.
+    fn query_subdir(&self, db: &mut Database, now: Epoch) -> Result<Option<(Dir<'static>, Rank)>> {
+        for split in (1..self.keywords.len()).rev() {
+            let (leading, trailing) = self.keywords.split_at(split);
+            let mut stream = self.get_stream(db, leading, now)?;
+            let Some((base, score)) = stream.next() else {
+                continue;
+            };
+
+            let Some(path) = self.find_subdir(Path::new(base.path.as_ref()), trailing)? else {
+                continue;
+            };
+            let path = util::path_to_str(&path)?;
+            if Some(path) != self.exclude.as_deref() {
+                // The subdirectory is ranked like the directory it was found in.
+                let dir = Dir {
+                    path: path.to_string().into(),
+                    rank: base.rank,
+                    last_accessed: base.last_accessed,
+                    pinned: false,
+                };
+                return Ok(Some((dir, score)));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Follows `keywords` down from `base`, one component at a time. Each
+    /// component is matched against the names of subdirectories like keywords
+    /// are matched against paths, or as a glob if it contains wildcards.
+    fn find_subdir(&self, base: &Path, keywords: &[String]) -> Result<Option<PathBuf>> {
+        let matching = self.matching();
+        let smart_case = self.smart_case || config::smart_case();
+        let mut path = base.to_path_buf();
+        for keyword in keywords {
+            // Regexes may contain separators that aren't part of the path.
+            let components = if matching == Matching::Regex {
+                vec![keyword.as_str()]
+            } else {
+                keyword.split(std::path::is_separator).filter(|c| !c.is_empty()).collect()
+            };
+            for component in components {
+                match component {
+                    "." => continue,
+                    ".." => return Ok(None),
+                    _ => {}
+                }
+                match Self::find_child(&path, component, matching, smart_case)? {
+                    Some(child) => path = child,
+                    None => return Ok(None),
+                }
+            }
+        }
+        Ok(Some(path))
+    }
+
+    /// Returns the subdirectory of `dir` that best matches `component`,
+    /// preferring an exact match, and then the shortest name.
+    fn find_child(
+        dir: &Path,
+        component: &str,
+        matching: Matching,
+        smart_case: bool,
+    ) -> Result<Option<PathBuf>> {
+        let case_sensitive = smart_case && component.chars().any(char::is_uppercase);
+        let is_match: Box<dyn Fn(&str) -> bool> =
+            if matching != Matching::Regex && component.contains(['*', '?', '[']) {
+                let pattern = Pattern::new(component)
+                    .with_context(|| format!("invalid glob: {component}"))?;
+                let options = MatchOptions { case_sensitive, ..MatchOptions::new() };
+                Box::new(move |name| pattern.matches_with(name, options))
+            } else {
+                let keywords = Keywords::new(&[component], matching, smart_case)?;
+                Box::new(move |name| keywords.is_match(name))
+            };
+
+        let Ok(entries) = fs::read_dir(dir) else {
+            return Ok(None);
+        };
+        let name = entries
+            .filter_map(|entry| entry.ok()?.file_name().into_string().ok())
+            .filter(|name| is_match(name) && dir.join(name).is_dir())
+            .min_by_key(|name| {
+                let exact = if case_sensitive {
+                    name == component
+                } else {
+                    util::to_lowercase(name) == util::to_lowercase(component)
+                };
+                (!exact, name.len(), name.clone())
+            });
+        Ok(name.map(|name| dir.join(name)))
+    }
+
+    /// Returns the path of the alias being queried, if the only keyword is of
+    /// the form `@name`.
+    fn get_alias<'a>(&self, db: &'a Database) -> Option<&'a str> {
+        match self.keywords.as_slice() {
+            [keyword] => {
+                let name = keyword.strip_prefix('@')?;
+                db.alias(name).map(|alias| alias.path.as_str())
+            }
+            _ => None,
+        }
+    }
+
+    fn get_stream<'a>(
+        &self,
+        db: &'a mut Database,
+        keywords: &[String],
+        now: Epoch,
+    ) -> Result<Stream<'a>> {
         let mut options = StreamOptions::new(now)
-            .with_keywords(self.keywords.iter().map(|s| s.as_str()))
+            .with_keywords(keywords.iter().map(|s| s.as_str()))
+            .with_matching(self.matching())
+            .with_smart_case(self.smart_case || config::smart_case())
             .with_exclude(config::exclude_dirs()?)
-            .with_base_dir(self.base_dir.clone());
+            .with_base_dir(self.base_dir.clone())
+            .with_proximity(Self::get_proximity()?);
         if !self.all {
             let resolve_symlinks = config::resolve_symlinks();
             options = options.with_exists(true).with_resolve_symlinks(resolve_symlinks);
         }
 
-        let stream = Stream::new(db, options);
-        Ok(stream)
+        Stream::new(db, options)
+    }
+
+    fn get_proximity() -> Result<Option<Proximity>> {
+        let weight = config::proximity()?;
+        if weight == 0.0 {
+            return Ok(None);
+        }
+        // The current directory may have been deleted, in which case there is
+        // nothing to be close to.
+        let Ok(current_dir) = util::current_dir() else {
+            return Ok(None);
+        };
+        Ok(Some(Proximity::from_current_dir(&current_dir, weight)))
+    }
+
+    fn keywords(&self) -> Result<Keywords> {
+        let smart_case = self.smart_case || config::smart_case();
+        Keywords::new(&self.keywords, self.matching(), smart_case)
     }
 
-    fn get_fzf() -> Result<FzfChild> {
-        let mut fzf = Fzf::new()?;
+    fn matching(&self) -> Matching {
+        if self.regex {
+            Matching::Regex
+        } else if self.fuzzy || config::fuzzy() {
+            Matching::Fuzzy
+        } else {
+            Matching::Substring
+        }
+    }
+
+    fn get_picker(&self) -> Result<PickerChild> {
+        let mut picker = Picker::new()?;
         if let Some(fzf_opts) = config::fzf_opts() {
-            fzf.env("FZF_DEFAULT_OPTS", fzf_opts)
+            picker.env("FZF_DEFAULT_OPTS", fzf_opts)
         } else {
-            fzf.args([
-                // Search mode
-                "--exact",
-                // Search result
-                "--no-sort",
-                // Interface
-                "--bind=ctrl-z:ignore,btab:up,tab:down",
-                "--cycle",
-                "--keep-right",
-                // Layout
-                "--border=sharp", // rounded edges don't display correctly on some terminals
-                "--height=45%",
-                "--info=inline",
-                "--layout=reverse",
-                // Display
-                "--tabstop=1",
-                // Scripting
-                "--exit-0",
-            ])
-            .enable_preview()
-        }
-        .spawn()
+            picker
+                .args([
+                    // Search mode
+                    "--exact",
+                    // Search result
+                    "--no-sort",
+                    // Interface
+                    "--bind=ctrl-z:ignore,btab:up,tab:down",
+                    "--cycle",
+                    "--keep-right",
+                    // Layout
+                    "--border=sharp", // rounded edges don't display correctly on some terminals
+                    "--height=45%",
+                    "--info=inline",
+                    "--layout=reverse",
+                    // Display
+                    "--tabstop=1",
+                    // Scripting
+                    "--exit-0",
+                ])
+                .enable_preview()
+        }
+        .builtin(|picker| {
+            let smart_case = self.smart_case || config::smart_case();
+            picker.preview(true).matching(self.matching(), smart_case);
+        });
+
+        if self.multi {
+            picker
+                .multi(&[Self::KEY_REMOVE, Self::KEY_OPEN])
+                .args([format!("--header={}", Self::MULTI_HEADER)])
+                .builtin(|picker| {
+                    picker.header(Self::MULTI_HEADER);
+                });
+        }
+        picker.spawn()
+    }
+}
+
+/// Asks the user to confirm an action on stderr, since stdout may be captured.
+/// Anything other than a yes is taken as a no.
+fn confirm(prompt: &str) -> Result<bool> {
+    eprint!("zoxide: {prompt} [y/N] ");
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer).context("could not read confirmation")?;
+    Ok(matches!(answer.trim(), "y" | "Y" | "yes" | "Yes"))
+}
+
+/// Writes records as a JSON array.
+fn write_records(handle: &mut impl Write, records: &[Record]) -> Result<()> {
+    serde_json::to_writer_pretty(&mut *handle, records)
+        .map_err(io::Error::from)
+        .pipe_exit("stdout")?;
+    writeln!(handle).pipe_exit("stdout")
+}
+
+/// Opens a directory with the default file manager.
+fn open_dir(path: &str) -> Result<()> {
+    let (program, args): (&str, &[&str]) = if cfg!(windows) {
+        ("cmd", &["/C", "start", ""])
+    } else if cfg!(target_os = "macos") {
+        ("open", &[])
+    } else {
+        ("xdg-open", &[])
+    };
+    let status = Command::new(program)
+        .args(args)
+        .arg(path)
+        .stdout(Stdio::null())
+        .status()
+        .with_context(|| format!("could not launch {program}"))?;
+    ensure!(status.success(), "could not open directory: {path}");
+    Ok(())
+}
+
+/// A query result, as printed with `--format json` or `--format jsonl`: the
+/// fields written by `zoxide export`, along with the score and whether the
+/// directory exists.
+#[derive(Serialize)]
+struct Record {
+    #[serde(flatten)]
+    dir: DirRecord<'static>,
+    score: Rank,
+    exists: bool,
+}
+
+impl Record {
+    fn new(dir: &Dir, score: Rank, exists: bool) -> Self {
+        let dir = DirRecord {
+            path: dir.path.to_string().into(),
+            rank: dir.rank,
+            last_accessed: dir.last_accessed,
+            pinned: dir.pinned,
+        };
+        Record { dir, score, exists }
     }
 }
@@ -1,15 +1,46 @@
-use anyhow::{Result, bail};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use glob::Pattern;
 
 use crate::cmd::{Remove, Run};
 use crate::db::Database;
-use crate::util;
+use crate::error::BrokenPipeHandler;
+use crate::{config, util};
 
 impl Run for Remove {
     fn run(&self) -> Result<()> {
         let mut db = Database::open()?;
 
-        for path in &self.paths {
-            if !db.remove(path) {
+        if self.forgotten {
+            let handle = &mut io::stdout().lock();
+            for tombstone in db.tombstones() {
+                writeln!(handle, "{}", tombstone.glob).pipe_exit("stdout")?;
+            }
+            return Ok(());
+        }
+
+        let mut paths = self.paths.clone();
+        if self.stdin0 {
+            paths.extend(util::read_stdin0()?);
+        }
+
+        for path in &paths {
+            if self.forget {
+                db.forget(Self::tombstone(&db, path)?);
+            } else if self.unforget {
+                // Tombstones may be given as written, or as the path of a
+                // directory, which `--forget` stores escaped.
+                let path_abs = util::resolve_path(path)?;
+                let path_abs = util::path_to_str(&path_abs)?;
+                let mut tombstones = vec![path.clone(), path_abs.to_string()];
+                tombstones.push(Pattern::escape(path_abs));
+                tombstones.extend(Self::tombstone(&db, path).ok());
+                if !tombstones.iter().any(|tombstone| db.unforget(tombstone)) {
+                    bail!("path not forgotten: {path}")
+                }
+            } else if !db.remove(path) {
                 let path_abs = util::resolve_path(path)?;
                 let path_abs = util::path_to_str(&path_abs)?;
                 if path_abs == path || !db.remove(path_abs) {
@@ -21,3 +52,26 @@ impl Run for Remove {
         db.save()
     }
 }
+
+impl Remove {
+    /// Returns the tombstone for `path`. Directories that exist or are in the
+    /// database are forgotten literally, even if their names contain glob
+    /// characters. Anything else is treated as a glob.
+    fn tombstone(db: &Database, path: &str) -> Result<String> {
+        let path_abs = util::resolve_path(path)?;
+        let path_abs = util::path_to_str(&path_abs)?;
+        if db.dirs().iter().any(|dir| dir.path == path_abs) {
+            return Ok(Pattern::escape(path_abs));
+        }
+        if Path::new(path_abs).is_dir() {
+            let path = if config::resolve_symlinks() {
+                util::canonicalize(path_abs)?
+            } else {
+                PathBuf::from(path_abs)
+            };
+            return Ok(Pattern::escape(util::path_to_str(&path)?));
+        }
+        Pattern::new(path_abs).with_context(|| format!("invalid glob: {path_abs}"))?;
+        Ok(path_abs.to_string())
+    }
+}
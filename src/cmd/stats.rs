@@ -0,0 +1,161 @@
+use std::cmp::Reverse;
+use std::fmt::Write as _;
+use std::fs;
+use std::io::{self, Write};
+
+use anyhow::Result;
+
+use crate::cmd::{Run, Stats};
+use crate::config;
+use crate::db::{Database, Dir, Epoch, MAX_SIZE, RECENCY, recency_bucket};
+use crate::error::BrokenPipeHandler;
+use crate::util;
+
+impl Run for Stats {
+    fn run(&self) -> Result<()> {
+        let now = util::current_time()?;
+        let max_age = config::maxage()?;
+        let resolve_symlinks = config::resolve_symlinks();
+
+        let mut db = Database::open()?;
+        db.save()?;
+        let dirs = db.dirs();
+
+        let mut out = String::new();
+        write_summary(&mut out, &db, max_age);
+        write_ages(&mut out, dirs, now);
+        write_top(&mut out, dirs, now, self.top);
+        write_missing(&mut out, dirs, resolve_symlinks);
+
+        io::stdout().write_all(out.as_bytes()).pipe_exit("stdout")
+    }
+}
+
+fn write_summary(out: &mut String, db: &Database, max_age: f64) {
+    let dirs = db.dirs();
+    let pinned = dirs.iter().filter(|dir| dir.pinned).count();
+    // Pinned entries don't count towards _ZO_MAXAGE.
+    let total_rank: f64 = dirs.iter().filter(|dir| !dir.pinned).map(|dir| dir.rank).sum();
+    let size = db.size();
+
+    _ = writeln!(out, "Entries         {} ({pinned} pinned)", dirs.len());
+    _ = writeln!(
+        out,
+        "Total rank      {total_rank:.1} / {max_age} (_ZO_MAXAGE), {:.1}%",
+        percent(total_rank, max_age)
+    );
+    _ = writeln!(
+        out,
+        "Database size   {} / {}, {:.1}%",
+        format_size(size),
+        format_size(MAX_SIZE),
+        percent(size as f64, MAX_SIZE as f64)
+    );
+}
+
+/// Counts entries by the age buckets used by [`Dir::score`].
+fn write_ages(out: &mut String, dirs: &[Dir], now: Epoch) {
+    let mut counts = [0; RECENCY.len()];
+    for dir in dirs {
+        counts[recency_bucket(dir.last_accessed, now)] += 1;
+    }
+
+    _ = writeln!(out, "\nLast accessed");
+    for ((_, label, _), count) in RECENCY.iter().zip(counts) {
+        _ = writeln!(out, "  {label:<20}{count:>8}");
+    }
+}
+
+fn write_top(out: &mut String, dirs: &[Dir], now: Epoch, top: usize) {
+    let mut by_rank = dirs.iter().collect::<Vec<_>>();
+    by_rank.sort_unstable_by(|dir1, dir2| dir2.rank.total_cmp(&dir1.rank));
+    _ = writeln!(out, "\nTop by rank");
+    for dir in by_rank.iter().take(top) {
+        _ = writeln!(out, "  {:>8.1}  {}", dir.rank, dir.path);
+    }
+
+    let mut by_recency = by_rank;
+    by_recency.sort_by_key(|dir| Reverse(dir.last_accessed));
+    _ = writeln!(out, "\nMost recent");
+    for dir in by_recency.iter().take(top) {
+        let ago = util::format_duration(now.saturating_sub(dir.last_accessed));
+        _ = writeln!(out, "  {ago:>8}  {}", dir.path);
+    }
+}
+
+fn write_missing(out: &mut String, dirs: &[Dir], resolve_symlinks: bool) {
+    // Same check as the one used by queries, which remove missing entries
+    // once they haven't been accessed for a while.
+    let resolver = if resolve_symlinks { fs::symlink_metadata } else { fs::metadata };
+    let missing = dirs
+        .iter()
+        .filter(|dir| !resolver(dir.path.as_ref()).is_ok_and(|metadata| metadata.is_dir()))
+        .collect::<Vec<_>>();
+
+    _ = writeln!(out, "\nMissing         {}", missing.len());
+    for dir in missing {
+        _ = writeln!(out, "  {}", dir.path);
+    }
+}
+
+fn percent(value: f64, total: f64) -> f64 {
+    if total == 0.0 { 0.0 } else { value / total * 100.0 }
+}
+
+fn format_size(bytes: u64) -> String {
+    const KIB: u64 = 1 << 10;
+    const MIB: u64 = 1 << 20;
+    match bytes {
+        _ if bytes < KIB => format!("{bytes} B"),
+        _ if bytes < MIB => format!("{:.1} KiB", bytes as f64 / KIB as f64),
+        _ => format!("{:.1} MiB", bytes as f64 / MIB as f64),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case(0, "0 B")]
+    #[case(1023, "1023 B")]
+    #[case(1024, "1.0 KiB")]
+    #[case(1536, "1.5 KiB")]
+    #[case((1 << 20) - 1, "1024.0 KiB")]
+    #[case(1 << 20, "1.0 MiB")]
+    #[case(MAX_SIZE, "32.0 MiB")]
+    fn size(#[case] bytes: u64, #[case] expected: &str) {
+        assert_eq!(format_size(bytes), expected);
+    }
+
+    #[test]
+    fn ages() {
+        use crate::util::{DAY, HOUR, WEEK};
+
+        let now = 10 * WEEK;
+        let dirs = [0, HOUR - 1, HOUR, DAY, WEEK - 1, WEEK, now].map(|age| Dir {
+            path: "/".into(),
+            rank: 1.0,
+            last_accessed: now - age,
+            pinned: false,
+        });
+        let mut out = String::new();
+        write_ages(&mut out, &dirs, now);
+        let counts = out
+            .lines()
+            .skip(2)
+            .map(|line| line.split_whitespace().last().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(counts, ["2", "1", "2", "2"]);
+    }
+
+    #[rstest]
+    #[case(0.0, 0.0, 0.0)]
+    #[case(1.0, 0.0, 0.0)]
+    #[case(5.0, 10.0, 50.0)]
+    fn percent_of(#[case] value: f64, #[case] total: f64, #[case] expected: f64) {
+        assert_eq!(percent(value, total), expected);
+    }
+}
@@ -2,7 +2,7 @@ use std::env;
 use std::ffi::OsString;
 use std::path::PathBuf;
 
-use anyhow::{Context, Result, ensure};
+use anyhow::{Context, Result, bail, ensure};
 use glob::Pattern;
 
 use crate::db::Rank;
@@ -47,6 +47,10 @@ pub fn fzf_opts() -> Option<OsString> {
     env::var_os("_ZO_FZF_OPTS")
 }
 
+pub fn fuzzy() -> bool {
+    env::var_os("_ZO_FUZZY").is_some_and(|var| var == "1")
+}
+
 pub fn maxage() -> Result<Rank> {
     env::var_os("_ZO_MAXAGE").map_or(Ok(10_000.0), |maxage| {
         let maxage = maxage.to_str().context("invalid unicode in _ZO_MAXAGE")?;
@@ -57,6 +61,169 @@ pub fn maxage() -> Result<Rank> {
     })
 }
 
+/// The interactive picker to use. By default, fzf is used if it is installed,
+/// falling back to the built-in picker otherwise.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PickerKind {
+    Builtin,
+    Fzf,
+    Skim,
+    Fzy,
+    Peco,
+    /// Runs `_ZO_PICKER_COMMAND`.
+    Custom,
+}
+
+pub fn picker() -> Result<Option<PickerKind>> {
+    let Some(picker) = env::var_os("_ZO_PICKER") else {
+        return Ok(None);
+    };
+    match picker.to_str().context("invalid unicode in _ZO_PICKER")? {
+        "" => Ok(None),
+        "builtin" => Ok(Some(PickerKind::Builtin)),
+        "fzf" => Ok(Some(PickerKind::Fzf)),
+        "sk" | "skim" => Ok(Some(PickerKind::Skim)),
+        "fzy" => Ok(Some(PickerKind::Fzy)),
+        "peco" => Ok(Some(PickerKind::Peco)),
+        "custom" => Ok(Some(PickerKind::Custom)),
+        picker => bail!("invalid value for _ZO_PICKER: {picker}"),
+    }
+}
+
+pub fn picker_command() -> Option<OsString> {
+    env::var_os("_ZO_PICKER_COMMAND").filter(|command| !command.is_empty())
+}
+
+/// How entries are passed to a picker, and how it returns the selection.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct Framing {
+    /// Entries are NUL-separated, rather than newline-separated.
+    pub read0: bool,
+    /// The selection is NUL-terminated, rather than newline-terminated.
+    pub print0: bool,
+    /// Entries are `<score>\t<path>`, rather than just the path.
+    pub scores: bool,
+}
+
+pub fn picker_framing() -> Result<Framing> {
+    let Some(flags) = env::var_os("_ZO_PICKER_FRAMING") else {
+        return Ok(Framing::default());
+    };
+    let flags = flags.to_str().context("invalid unicode in _ZO_PICKER_FRAMING")?;
+    parse_framing(flags)
+}
+
+/// Parses a comma-separated list of framing flags.
+fn parse_framing(flags: &str) -> Result<Framing> {
+    let mut framing = Framing::default();
+    for flag in flags.split(',').map(str::trim).filter(|flag| !flag.is_empty()) {
+        match flag {
+            "read0" => framing.read0 = true,
+            "print0" => framing.print0 = true,
+            "scores" => framing.scores = true,
+            flag => bail!("invalid value for _ZO_PICKER_FRAMING: {flag}"),
+        }
+    }
+    Ok(framing)
+}
+
+/// How directories are previewed in the interactive picker.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub enum Preview {
+    #[default]
+    Ls,
+    Eza,
+    Lsd,
+    Tree,
+    /// `git status`, followed by a listing.
+    Git,
+    /// `zoxide preview`.
+    Zoxide,
+    None,
+    /// A shell command, in which `{}` is replaced by the path.
+    Command(String),
+}
+
+pub fn preview() -> Result<Preview> {
+    let Some(preview) = env::var_os("_ZO_PREVIEW") else {
+        return Ok(Preview::default());
+    };
+    let preview = preview.to_str().context("invalid unicode in _ZO_PREVIEW")?;
+    Ok(parse_preview(preview))
+}
+
+/// Parses a preset, falling back to treating the value as a command.
+fn parse_preview(preview: &str) -> Preview {
+    match preview {
+        "" | "ls" => Preview::Ls,
+        "eza" => Preview::Eza,
+        "lsd" => Preview::Lsd,
+        "tree" => Preview::Tree,
+        "git" => Preview::Git,
+        "zoxide" => Preview::Zoxide,
+        "none" => Preview::None,
+        command => Preview::Command(command.to_string()),
+    }
+}
+
+pub fn preview_window() -> Option<OsString> {
+    env::var_os("_ZO_PREVIEW_WINDOW").filter(|window| !window.is_empty())
+}
+
+pub fn proximity() -> Result<Rank> {
+    env::var_os("_ZO_PROXIMITY").map_or(Ok(0.0), |proximity| {
+        let proximity = proximity.to_str().context("invalid unicode in _ZO_PROXIMITY")?;
+        let proximity = proximity
+            .parse::<Rank>()
+            .ok()
+            .filter(|proximity| proximity.is_finite() && *proximity >= 0.0)
+            .with_context(|| format!("unable to parse _ZO_PROXIMITY as number: {proximity}"))?;
+        Ok(proximity)
+    })
+}
+
 pub fn resolve_symlinks() -> bool {
     env::var_os("_ZO_RESOLVE_SYMLINKS").is_some_and(|var| var == "1")
 }
+
+pub fn smart_case() -> bool {
+    env::var_os("_ZO_SMART_CASE").is_some_and(|var| var == "1")
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("", Framing::default())]
+    #[case("read0", Framing { read0: true, print0: false, scores: false })]
+    #[case("print0,scores", Framing { read0: false, print0: true, scores: true })]
+    #[case(" read0 , print0 ,scores,", Framing { read0: true, print0: true, scores: true })]
+    fn framing(#[case] flags: &str, #[case] expected: Framing) {
+        assert_eq!(parse_framing(flags).unwrap(), expected);
+    }
+
+    #[rstest]
+    #[case("", Preview::Ls)]
+    #[case("ls", Preview::Ls)]
+    #[case("eza", Preview::Eza)]
+    #[case("lsd", Preview::Lsd)]
+    #[case("tree", Preview::Tree)]
+    #[case("git", Preview::Git)]
+    #[case("zoxide", Preview::Zoxide)]
+    #[case("none", Preview::None)]
+    #[case("LS", Preview::Command("LS".to_string()))]
+    #[case("bat {}/README.md", Preview::Command("bat {}/README.md".to_string()))]
+    fn preview(#[case] value: &str, #[case] expected: Preview) {
+        assert_eq!(parse_preview(value), expected);
+    }
+
+    #[rstest]
+    #[case("read1")]
+    #[case("read0,Print0")]
+    fn framing_invalid(#[case] flags: &str) {
+        assert!(parse_framing(flags).is_err());
+    }
+}
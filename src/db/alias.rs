@@ -0,0 +1,8 @@
+use serde::{Deserialize, Serialize};
+
+/// A name that can be used to jump directly to a directory, as in `z @name`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Alias {
+    pub name: String,
+    pub path: String,
+}
@@ -0,0 +1,26 @@
+use serde::{Deserialize, Serialize};
+
+use crate::db::dir;
+use crate::db::{Epoch, Rank};
+
+/// Records that a query with `keywords` led to `path`, so that `path` can be
+/// ranked higher the next time the same keywords are used.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Association {
+    pub keywords: String,
+    pub path: String,
+    pub rank: Rank,
+    pub last_accessed: Epoch,
+}
+
+impl Association {
+    /// Associations are aged like directories, but with a fixed limit rather
+    /// than `_ZO_MAXAGE`.
+    pub const MAX_AGE: Rank = 1000.0;
+
+    /// Returns the factor by which the score of the associated directory is
+    /// multiplied.
+    pub fn boost(&self, now: Epoch) -> Rank {
+        1.0 + dir::frecency(self.rank, self.last_accessed, now)
+    }
+}
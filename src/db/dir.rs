@@ -11,6 +11,10 @@ pub struct Dir<'a> {
     pub path: Cow<'a, str>,
     pub rank: Rank,
     pub last_accessed: Epoch,
+    /// Pinned directories are never aged out, and are ranked above unpinned
+    /// ones. This is stored in a separate section of the database.
+    #[serde(skip)]
+    pub pinned: bool,
 }
 
 impl Dir<'_> {
@@ -19,33 +23,86 @@ impl Dir<'_> {
     }
 
     pub fn score(&self, now: Epoch) -> Rank {
-        // The older the entry, the lesser its importance.
-        let duration = now.saturating_sub(self.last_accessed);
-        if duration < HOUR {
-            self.rank * 4.0
-        } else if duration < DAY {
-            self.rank * 2.0
-        } else if duration < WEEK {
-            self.rank * 0.5
-        } else {
-            self.rank * 0.25
+        frecency(self.rank, self.last_accessed, now)
+    }
+}
+
+/// Weighs `rank` by how long ago `last_accessed` was.
+pub fn frecency(rank: Rank, last_accessed: Epoch, now: Epoch) -> Rank {
+    rank * recency(last_accessed, now).1
+}
+
+/// Age buckets, from newest to oldest: the age below which entries fall into
+/// each bucket, its description, and the multiplier applied to the rank of
+/// entries in it. The older the entry, the lesser its importance.
+pub const RECENCY: [(Epoch, &str, Rank); 4] = [
+    (HOUR, "within the last hour", 4.0),
+    (DAY, "within the last day", 2.0),
+    (WEEK, "within the last week", 0.5),
+    (Epoch::MAX, "more than a week ago", 0.25),
+];
+
+/// Returns the age bucket that `last_accessed` falls into, along with the
+/// multiplier applied to the rank of entries in it.
+pub fn recency(last_accessed: Epoch, now: Epoch) -> (&'static str, Rank) {
+    let (_, label, factor) = RECENCY[recency_bucket(last_accessed, now)];
+    (label, factor)
+}
+
+/// Returns the index in [`RECENCY`] of the bucket that `last_accessed` falls
+/// into.
+pub fn recency_bucket(last_accessed: Epoch, now: Epoch) -> usize {
+    let duration = now.saturating_sub(last_accessed);
+    RECENCY.iter().position(|&(age, ..)| duration < age).unwrap_or(RECENCY.len() - 1)
+}
+
+/// A directory with every field of [`Dir`], as written by `zoxide export` and
+/// read by `zoxide import json`.
+#[derive(Debug, Deserialize, Serialize)]
+pub struct DirRecord<'a> {
+    #[serde(borrow)]
+    pub path: Cow<'a, str>,
+    pub rank: Rank,
+    pub last_accessed: Epoch,
+    #[serde(default)]
+    pub pinned: bool,
+}
+
+impl<'a> From<&'a Dir<'_>> for DirRecord<'a> {
+    fn from(dir: &'a Dir<'_>) -> Self {
+        DirRecord {
+            path: Cow::Borrowed(&dir.path),
+            rank: dir.rank,
+            last_accessed: dir.last_accessed,
+            pinned: dir.pinned,
+        }
+    }
+}
+
+impl From<DirRecord<'_>> for Dir<'static> {
+    fn from(record: DirRecord<'_>) -> Self {
+        Dir {
+            path: Cow::Owned(record.path.into_owned()),
+            rank: record.rank,
+            last_accessed: record.last_accessed,
+            pinned: record.pinned,
         }
     }
 }
 
 pub struct DirDisplay<'a> {
     dir: &'a Dir<'a>,
-    now: Option<Epoch>,
+    score: Option<Rank>,
     separator: char,
 }
 
 impl<'a> DirDisplay<'a> {
     fn new(dir: &'a Dir) -> Self {
-        Self { dir, separator: ' ', now: None }
+        Self { dir, separator: ' ', score: None }
     }
 
-    pub fn with_score(mut self, now: Epoch) -> Self {
-        self.now = Some(now);
+    pub fn with_score(mut self, score: Rank) -> Self {
+        self.score = Some(score);
         self
     }
 
@@ -57,9 +114,10 @@ impl<'a> DirDisplay<'a> {
 
 impl Display for DirDisplay<'_> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        if let Some(now) = self.now {
-            let score = self.dir.score(now).clamp(0.0, 9999.0);
-            write!(f, "{score:>6.1}{}", self.separator)?;
+        if let Some(score) = self.score {
+            let score = score.clamp(0.0, 9999.0);
+            let pinned = if self.dir.pinned { '*' } else { ' ' };
+            write!(f, "{score:>6.1}{pinned}{}", self.separator)?;
         }
         write!(f, "{}", self.dir.path)
     }
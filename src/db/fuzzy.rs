@@ -0,0 +1,67 @@
+//! Fuzzy matching of query keywords against paths.
+
+use std::path;
+
+use crate::db::keyword::Keyword;
+
+/// Score for a matched character that doesn't start a word and doesn't follow
+/// the previous match.
+const SCORE_MATCH: u32 = 1;
+
+/// Bonus for a matched character that starts a word, or directly follows the
+/// previous match.
+const BONUS_CONTIGUOUS: u32 = 2;
+
+/// Returns how well `keywords` match `path`, from 0 to 1, or `None` if they
+/// don't match.
+///
+/// The keywords match if their characters appear in `path` in order, not
+/// necessarily next to each other, and the last character is found in the last
+/// component of `path`. Of all such matches, the one with the most characters
+/// that start words or continue the previous match is used.
+pub fn quality(keywords: &[Keyword], path: &str) -> Option<f64> {
+    let pattern = keywords
+        .iter()
+        .flat_map(|keyword| keyword.text.chars().map(move |c| (keyword, c)))
+        .collect::<Vec<_>>();
+    if pattern.is_empty() {
+        return Some(1.0);
+    }
+
+    let path = path.chars().collect::<Vec<_>>();
+    let last_component = path.iter().rposition(|&c| path::is_separator(c)).map_or(0, |idx| idx + 1);
+
+    // scores[j] is the best score for matching the pattern so far, with the
+    // last matched character at path[j].
+    let mut scores = vec![None::<u32>; path.len()];
+    for (i, &(keyword, c)) in pattern.iter().enumerate() {
+        let mut next = vec![None; path.len()];
+        // Best score with the previous character matched before path[j - 1].
+        let mut best_gap = None;
+        for j in 0..path.len() {
+            if j >= 2 {
+                best_gap = best_gap.max(scores[j - 2]);
+            }
+            if !keyword.char_matches(path[j], c) {
+                continue;
+            }
+
+            let word_start = j == 0 || !path[j - 1].is_alphanumeric();
+            let score = |contiguous: bool| {
+                if contiguous || word_start { SCORE_MATCH + BONUS_CONTIGUOUS } else { SCORE_MATCH }
+            };
+            next[j] = if i == 0 {
+                Some(score(false))
+            } else {
+                let contiguous = j.checked_sub(1).and_then(|j| scores[j]).map(|s| s + score(true));
+                let gap = best_gap.map(|s| s + score(false));
+                contiguous.max(gap)
+            };
+        }
+        scores = next;
+    }
+
+    let best = scores.get(last_component..)?.iter().flatten().max()?;
+    let max = pattern.len() as u32 * (SCORE_MATCH + BONUS_CONTIGUOUS);
+    Some(*best as f64 / max as f64)
+}
@@ -0,0 +1,131 @@
+use std::borrow::Cow;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::PathBuf;
+
+use anyhow::{Context, Result};
+use bincode::Options;
+use serde::{Deserialize, Serialize};
+
+use crate::db::{Epoch, Rank};
+
+/// An append-only log of changes that have not yet been written to the main
+/// database file. Appending a small record is much cheaper than rewriting the
+/// entire database, which matters when `zoxide add` runs on every prompt.
+#[derive(Debug, Default)]
+pub struct Journal {
+    path: PathBuf,
+
+    /// Size of the journal file on disk.
+    len: u64,
+
+    /// Serialized records that are yet to be appended to the journal file.
+    pending: Vec<u8>,
+}
+
+/// A single change recorded in the journal.
+#[derive(Debug, Deserialize, Serialize)]
+pub enum Record<'a> {
+    /// See [`crate::db::Database::add`].
+    Add { path: Cow<'a, str>, by: Rank, now: Epoch },
+    /// See [`crate::db::Database::add_update`].
+    AddUpdate { path: Cow<'a, str>, by: Rank, now: Epoch },
+    /// See [`crate::db::Database::learn`].
+    Learn { keywords: Cow<'a, str>, path: Cow<'a, str>, now: Epoch },
+}
+
+impl Journal {
+    /// Once the journal grows beyond this size, it is compacted into the main
+    /// database file on the next save. This bounds the time spent replaying
+    /// the journal when opening the database.
+    const MAX_SIZE: u64 = 64 << 10; // 64 KiB
+
+    /// Reads the journal at `path`, returning it along with its contents.
+    pub fn open(path: PathBuf) -> Result<(Self, Vec<u8>)> {
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Vec::new(),
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("could not read from journal: {}", path.display()));
+            }
+        };
+        let journal = Journal { path, len: bytes.len() as u64, pending: Vec::new() };
+        Ok((journal, bytes))
+    }
+
+    /// Parses the records in `bytes`. The last record may be incomplete if
+    /// zoxide was interrupted while appending to the journal, in which case
+    /// the iterator ends with an error.
+    pub fn records(mut bytes: &[u8]) -> impl Iterator<Item = Result<Record<'static>>> {
+        std::iter::from_fn(move || {
+            if bytes.is_empty() {
+                return None;
+            }
+            let record = Self::options()
+                .deserialize_from(&mut bytes)
+                .context("could not deserialize journal: corrupted data");
+            if record.is_err() {
+                bytes = &[];
+            }
+            Some(record)
+        })
+    }
+
+    pub fn push(&mut self, record: &Record) {
+        // Serializing to a Vec can't fail.
+        Self::options().serialize_into(&mut self.pending, record).unwrap();
+    }
+
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    /// Returns true if the journal should be compacted into the database.
+    pub fn is_full(&self) -> bool {
+        self.len + self.pending.len() as u64 > Self::MAX_SIZE
+    }
+
+    /// Appends pending records to the journal file.
+    pub fn flush(&mut self) -> Result<()> {
+        if self.pending.is_empty() {
+            return Ok(());
+        }
+
+        // No fsync here: a torn write only loses the last few records, which are
+        // skipped when replaying.
+        OpenOptions::new()
+            .append(true)
+            .create(true)
+            .open(&self.path)
+            .and_then(|mut file| file.write_all(&self.pending))
+            .with_context(|| format!("could not write to journal: {}", self.path.display()))?;
+
+        self.len += self.pending.len() as u64;
+        self.pending.clear();
+        Ok(())
+    }
+
+    /// Deletes the journal, after its contents have been written to the main
+    /// database file.
+    pub fn clear(&mut self) -> Result<()> {
+        self.pending.clear();
+        if self.len == 0 {
+            return Ok(());
+        }
+
+        match fs::remove_file(&self.path) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => {
+                Err(e).with_context(|| format!("could not delete journal: {}", self.path.display()))
+            }
+            _ => {
+                self.len = 0;
+                Ok(())
+            }
+        }
+    }
+
+    fn options() -> impl Options {
+        bincode::options().with_fixint_encoding().with_limit(Self::MAX_SIZE)
+    }
+}
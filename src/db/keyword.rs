@@ -0,0 +1,243 @@
+//! Matching of query keywords against paths.
+
+use std::ops::Range;
+use std::path;
+
+use anyhow::{Context, Result};
+use regex::{Regex, RegexBuilder};
+
+use crate::db::fuzzy;
+use crate::util;
+
+/// How keywords are matched against paths.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub enum Matching {
+    /// Keywords are substrings of the path.
+    #[default]
+    Substring,
+    /// The characters of the keywords appear in the path in order.
+    Fuzzy,
+    /// Keywords are regular expressions that match part of the path.
+    Regex,
+}
+
+/// The keywords of a query, prepared for matching.
+pub struct Keywords {
+    keywords: Vec<Keyword>,
+    matching: Matching,
+}
+
+pub struct Keyword {
+    /// The keyword, lowercased unless it is case-sensitive.
+    pub text: String,
+    pub case_sensitive: bool,
+    regex: Option<Regex>,
+}
+
+impl Keywords {
+    /// With `smart_case`, a keyword that contains an uppercase letter is
+    /// matched case-sensitively. Otherwise, matching ignores case.
+    pub fn new<S: AsRef<str>>(
+        keywords: &[S],
+        matching: Matching,
+        smart_case: bool,
+    ) -> Result<Self> {
+        let keywords = keywords
+            .iter()
+            .map(|keyword| Keyword::new(keyword.as_ref(), matching, smart_case))
+            .collect::<Result<_>>()?;
+        Ok(Keywords { keywords, matching })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.keywords.is_empty()
+    }
+
+    /// Returns the keywords in the form that associations are stored in:
+    /// lowercased, unless they are matched case-sensitively.
+    pub fn key(&self) -> String {
+        self.keywords.iter().map(|keyword| keyword.text.as_str()).collect::<Vec<_>>().join(" ")
+    }
+
+    /// Returns true if the keywords match `path`.
+    ///
+    /// The keywords must appear in `path` in order, and the last keyword must
+    /// match within the last component of `path`.
+    pub fn is_match(&self, path: &str) -> bool {
+        if self.matching == Matching::Fuzzy {
+            return fuzzy::quality(&self.keywords, path).is_some();
+        }
+        self.find(path, |_, _| ())
+    }
+
+    /// Returns the byte range of `path` matched by each keyword, or `None` if
+    /// the keywords don't match. Fuzzy matches don't have a single range per
+    /// keyword, so no ranges are returned for them.
+    pub fn matches(&self, path: &str) -> Option<Vec<Range<usize>>> {
+        if self.matching == Matching::Fuzzy {
+            return self.is_match(path).then(Vec::new);
+        }
+        let mut matches = vec![0..0; self.keywords.len()];
+        self.find(path, |idx, range| matches[idx] = range).then_some(matches)
+    }
+
+    /// Matches the keywords right-to-left against `path`, calling `on_match`
+    /// with the index and range of each keyword as it is found.
+    fn find(&self, path: &str, mut on_match: impl FnMut(usize, Range<usize>)) -> bool {
+        let (keywords_last, keywords) = match self.keywords.split_last() {
+            Some(split) => split,
+            None => return true,
+        };
+
+        let mut haystack = path;
+        match keywords_last.rfind(haystack) {
+            Some((start, end)) => {
+                if haystack[end..].contains(path::is_separator) {
+                    return false;
+                }
+                on_match(keywords.len(), start..end);
+                haystack = &haystack[..start];
+            }
+            None => return false,
+        }
+
+        for (idx, keyword) in keywords.iter().enumerate().rev() {
+            match keyword.rfind(haystack) {
+                Some((start, end)) => {
+                    on_match(idx, start..end);
+                    haystack = &haystack[..start];
+                }
+                None => return false,
+            }
+        }
+
+        true
+    }
+
+    /// Returns how well the keywords match `path`, from 0 to 1, or `None` if
+    /// they don't match. Only fuzzy matches are graded, for anything else this
+    /// is always 1 and [`Keywords::is_match`] must be checked separately.
+    pub fn quality(&self, path: &str) -> Option<f64> {
+        match self.matching {
+            Matching::Fuzzy => fuzzy::quality(&self.keywords, path),
+            _ => Some(1.0),
+        }
+    }
+
+    /// Like [`Keywords::quality`], but also returns `None` for non-fuzzy
+    /// matching if the keywords don't match. In fuzzy mode, matching and
+    /// grading are the same work, so this avoids doing it twice.
+    pub fn grade(&self, path: &str) -> Option<f64> {
+        match self.matching {
+            Matching::Fuzzy => fuzzy::quality(&self.keywords, path),
+            _ => self.is_match(path).then_some(1.0),
+        }
+    }
+}
+
+impl Keyword {
+    fn new(keyword: &str, matching: Matching, smart_case: bool) -> Result<Self> {
+        let case_sensitive = smart_case && has_uppercase(keyword, matching);
+        let regex = match matching {
+            Matching::Regex => Some(
+                RegexBuilder::new(keyword)
+                    .case_insensitive(!case_sensitive)
+                    .build()
+                    .with_context(|| format!("invalid regex: {keyword}"))?,
+            ),
+            _ => None,
+        };
+        let text = if case_sensitive { keyword.to_string() } else { util::to_lowercase(keyword) };
+        Ok(Keyword { text, case_sensitive, regex })
+    }
+
+    /// Returns the byte range of the last match of this keyword in `haystack`.
+    fn rfind(&self, haystack: &str) -> Option<(usize, usize)> {
+        if let Some(regex) = &self.regex {
+            return regex.find_iter(haystack).last().map(|m| (m.start(), m.end()));
+        }
+        if self.case_sensitive {
+            return haystack.rfind(&self.text).map(|idx| (idx, idx + self.text.len()));
+        }
+        rfind_ignore_case(haystack, &self.text)
+    }
+
+    /// Returns true if `c` matches the character `k` of this keyword.
+    pub fn char_matches(&self, c: char, k: char) -> bool {
+        c == k || (!self.case_sensitive && c.to_lowercase().eq([k]))
+    }
+}
+
+/// Finds the last occurrence of the lowercase `needle` in `haystack`, ignoring
+/// the case of `haystack`.
+fn rfind_ignore_case(haystack: &str, needle: &str) -> Option<(usize, usize)> {
+    // ASCII can be compared byte by byte, without lowercasing a copy of the
+    // path on every query.
+    if haystack.is_ascii() {
+        let (haystack, needle) = (haystack.as_bytes(), needle.as_bytes());
+        let idx = (0..=haystack.len().checked_sub(needle.len())?)
+            .rev()
+            .find(|&idx| haystack[idx..idx + needle.len()].eq_ignore_ascii_case(needle))?;
+        return Some((idx, idx + needle.len()));
+    }
+    if needle.is_empty() {
+        return Some((haystack.len(), haystack.len()));
+    }
+
+    'outer: for (start, _) in haystack.char_indices().rev() {
+        let mut needle = needle.chars().peekable();
+        let mut end = start;
+        for c in haystack[start..].chars() {
+            if needle.peek().is_none() {
+                break;
+            }
+            if !c.to_lowercase().all(|c| needle.next() == Some(c)) {
+                continue 'outer;
+            }
+            end += c.len_utf8();
+        }
+        if needle.peek().is_none() {
+            return Some((start, end));
+        }
+    }
+    None
+}
+
+/// Returns true if `keyword` contains an uppercase letter. Escape sequences
+/// like `\W` in regular expressions are not counted, but backslashes in other
+/// keywords are literal, e.g. path separators on Windows.
+fn has_uppercase(keyword: &str, matching: Matching) -> bool {
+    let mut chars = keyword.chars();
+    while let Some(c) = chars.next() {
+        if c == '\\' && matching == Matching::Regex {
+            chars.next();
+        } else if c.is_uppercase() {
+            return true;
+        }
+    }
+    false
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("/Foo/foo", "foo", Some((5, 8)))]
+    #[case("/FOO/bar", "foo", Some((1, 4)))]
+    #[case("/foo", "", Some((4, 4)))]
+    #[case("/fo", "foo", None)]
+    #[case("/foo", "fö", None)]
+    #[case("/Föo/föo", "föo", Some((6, 10)))]
+    #[case("/FÖO/bar", "föo", Some((1, 5)))]
+    #[case("/fÖ", "foo", None)]
+    fn rfind(
+        #[case] haystack: &str,
+        #[case] needle: &str,
+        #[case] expected: Option<(usize, usize)>,
+    ) {
+        assert_eq!(rfind_ignore_case(haystack, needle), expected);
+    }
+}
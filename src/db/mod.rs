@@ -1,15 +1,32 @@
+mod alias;
+mod association;
 mod dir;
+mod fuzzy;
+mod journal;
+mod keyword;
+mod proximity;
+mod schema;
 mod stream;
+mod tombstone;
 
+use std::fs::{self, File, OpenOptions};
 use std::path::{Path, PathBuf};
-use std::{fs, io};
+use std::{io, mem};
 
-use anyhow::{Context, Result, bail};
-use bincode::Options;
+use anyhow::{Context, Result};
+use glob::Pattern;
 use ouroboros::self_referencing;
 
-pub use crate::db::dir::{Dir, Epoch, Rank};
-pub use crate::db::stream::{Stream, StreamOptions};
+pub use crate::db::alias::Alias;
+pub use crate::db::association::Association;
+pub use crate::db::dir::{Dir, DirRecord, Epoch, RECENCY, Rank, recency_bucket};
+use crate::db::journal::{Journal, Record};
+pub use crate::db::keyword::{Keywords, Matching};
+pub use crate::db::proximity::Proximity;
+use crate::db::schema::Extra;
+pub use crate::db::schema::{MAX_SIZE, VERSION, VERSION_MIN};
+pub use crate::db::stream::{Filtered, Stream, StreamOptions};
+pub use crate::db::tombstone::Tombstone;
 use crate::{config, util};
 
 #[self_referencing]
@@ -20,68 +37,166 @@ pub struct Database {
     #[covariant]
     pub dirs: Vec<Dir<'this>>,
     dirty: bool,
+    lock: Option<File>,
+    journal: Journal,
+    extra: Extra,
 }
 
 impl Database {
-    const VERSION: u32 = 3;
-
     pub fn open() -> Result<Self> {
         let data_dir = config::data_dir()?;
         Self::open_dir(data_dir)
     }
 
+    /// Opens the database, blocking until any other zoxide process that has it
+    /// open is done with it. The lock is held until [`Database::save`] is
+    /// called or the database is dropped, so updates made by concurrent
+    /// processes (e.g. shell hooks firing in several terminals at once) are
+    /// never overwritten.
     pub fn open_dir(data_dir: impl AsRef<Path>) -> Result<Self> {
         let data_dir = data_dir.as_ref();
+
+        // Create data directory, but don't create the database yet. The file will
+        // be created later by [`Database::save`] if any data is modified.
+        fs::create_dir_all(data_dir)
+            .with_context(|| format!("unable to create data directory: {}", data_dir.display()))?;
+
+        let path = Self::path(data_dir);
+        let lock = Self::lock(&path)?;
+        Self::read(path, lock)
+    }
+
+    /// Opens the database without waiting for the lock, for commands that
+    /// only read it and run often, like `zoxide preview`. The result may be
+    /// slightly out of date if another process is writing to it, and it must
+    /// not be saved.
+    pub fn open_read_only() -> Result<Self> {
+        let data_dir = config::data_dir()?;
+        Self::read(Self::path(&data_dir), None)
+    }
+
+    fn path(data_dir: &Path) -> PathBuf {
         let path = data_dir.join("db.zo");
-        let path = fs::canonicalize(&path).unwrap_or(path);
+        fs::canonicalize(&path).unwrap_or(path)
+    }
 
-        match fs::read(&path) {
-            Ok(bytes) => Self::try_new(path, bytes, |bytes| Self::deserialize(bytes), false),
+    /// Reads the database at `path`, along with its journal.
+    fn read(path: PathBuf, lock: Option<File>) -> Result<Self> {
+        let (journal, journal_bytes) = Journal::open(Self::sibling(&path, ".journal"))?;
+
+        let mut db = match fs::read(&path) {
+            Ok(bytes) => {
+                let mut extra = Extra::default();
+                let mut version = VERSION;
+                let mut db = Self::try_new(
+                    path,
+                    bytes,
+                    |bytes| {
+                        let contents = schema::deserialize(bytes)?;
+                        extra = contents.extra;
+                        version = contents.version;
+                        anyhow::Ok(contents.dirs)
+                    },
+                    false,
+                    lock,
+                    journal,
+                    Extra::default(),
+                )?;
+                db.with_extra_mut(|extra_db| *extra_db = extra);
+
+                // Upgrade databases created by older versions in place.
+                if version != VERSION {
+                    db.mark_dirty();
+                }
+                db
+            }
             Err(e) if e.kind() == io::ErrorKind::NotFound => {
-                // Create data directory, but don't create any file yet. The file will be
-                // created later by [`Database::save`] if any data is modified.
-                fs::create_dir_all(data_dir).with_context(|| {
-                    format!("unable to create data directory: {}", data_dir.display())
-                })?;
-                Ok(Self::new(path, Vec::new(), |_| Vec::new(), false))
+                Self::new(path, Vec::new(), |_| Vec::new(), false, lock, journal, Extra::default())
             }
             Err(e) => {
-                Err(e).with_context(|| format!("could not read from database: {}", path.display()))
+                return Err(e)
+                    .with_context(|| format!("could not read from database: {}", path.display()));
             }
-        }
+        };
+        db.replay(&journal_bytes);
+        Ok(db)
+    }
+
+    /// Creates an in-memory database, for testing.
+    #[cfg(test)]
+    pub fn from_dirs(dirs: Vec<Dir<'static>>) -> Self {
+        Self::new(
+            PathBuf::new(),
+            Vec::new(),
+            |_| dirs,
+            false,
+            None,
+            Journal::default(),
+            Extra::default(),
+        )
     }
 
+    /// Writes the database to disk if it has been modified, and releases the
+    /// lock acquired by [`Database::open`]. The in-memory copy can still be
+    /// read afterwards, but it may go stale as other processes write to disk.
     pub fn save(&mut self) -> Result<()> {
         // Only write to disk if the database is modified.
-        if !self.dirty() {
-            return Ok(());
+        if self.dirty() {
+            if *self.borrow_dirty() || self.borrow_journal().is_full() {
+                // Compact the journal into the database. If zoxide is interrupted
+                // between these two steps, the journal will be replayed twice,
+                // which at worst inflates the ranks of a few entries.
+                self.write(VERSION)?;
+            } else {
+                self.with_journal_mut(|journal| journal.flush())?;
+            }
+            self.with_dirty_mut(|dirty| *dirty = false);
         }
 
-        let bytes = Self::serialize(self.dirs())?;
-        util::write(self.borrow_path(), bytes).context("could not write to database")?;
+        self.with_lock_mut(|lock| *lock = None);
+        Ok(())
+    }
+
+    /// Rewrites the database in the format of the given version, and releases
+    /// the lock. Data that can't be represented in an older version is
+    /// dropped, and the names of the discarded sections are returned.
+    ///
+    /// This exists so that the database can be handed back to an older release
+    /// of zoxide. Opening it with this release will upgrade it again.
+    pub fn save_version(&mut self, version: u32) -> Result<Vec<String>> {
+        let dropped = schema::unsupported(self, version);
+
+        self.write(version)?;
         self.with_dirty_mut(|dirty| *dirty = false);
+        self.with_lock_mut(|lock| *lock = None);
+        Ok(dropped)
+    }
 
-        Ok(())
+    fn write(&mut self, version: u32) -> Result<()> {
+        let bytes = schema::serialize(self, version)?;
+        util::write(self.borrow_path(), bytes).context("could not write to database")?;
+        self.with_journal_mut(|journal| journal.clear())
     }
 
     /// Increments the rank of a directory, or creates it if it does not exist.
     pub fn add(&mut self, path: impl AsRef<str> + Into<String>, by: Rank, now: Epoch) {
-        self.with_dirs_mut(|dirs| match dirs.iter_mut().find(|dir| dir.path == path.as_ref()) {
-            Some(dir) => dir.rank = (dir.rank + by).max(0.0),
-            None => {
-                dirs.push(Dir { path: path.into().into(), rank: by.max(0.0), last_accessed: now })
-            }
-        });
-        self.with_dirty_mut(|dirty| *dirty = true);
+        self.log(&Record::Add { path: path.as_ref().into(), by, now });
+        self.upsert(path, by, now, false);
     }
 
     /// Creates a new directory. This will create a duplicate entry if this
     /// directory is already in the database, it is expected that the user
     /// either does a check before calling this, or calls `dedup()`
     /// afterward.
-    pub fn add_unchecked(&mut self, path: impl AsRef<str> + Into<String>, rank: Rank, now: Epoch) {
+    pub fn add_unchecked(
+        &mut self,
+        path: impl AsRef<str> + Into<String>,
+        rank: Rank,
+        now: Epoch,
+        pinned: bool,
+    ) {
         self.with_dirs_mut(|dirs| {
-            dirs.push(Dir { path: path.into().into(), rank, last_accessed: now })
+            dirs.push(Dir { path: path.into().into(), rank, last_accessed: now, pinned })
         });
         self.with_dirty_mut(|dirty| *dirty = true);
     }
@@ -89,16 +204,47 @@ impl Database {
     /// Increments the rank and updates the last_accessed of a directory, or
     /// creates it if it does not exist.
     pub fn add_update(&mut self, path: impl AsRef<str> + Into<String>, by: Rank, now: Epoch) {
+        self.log(&Record::AddUpdate { path: path.as_ref().into(), by, now });
+        self.upsert(path, by, now, true);
+    }
+
+    fn upsert(&mut self, path: impl AsRef<str> + Into<String>, by: Rank, now: Epoch, update: bool) {
         self.with_dirs_mut(|dirs| match dirs.iter_mut().find(|dir| dir.path == path.as_ref()) {
             Some(dir) => {
                 dir.rank = (dir.rank + by).max(0.0);
-                dir.last_accessed = now;
-            }
-            None => {
-                dirs.push(Dir { path: path.into().into(), rank: by.max(0.0), last_accessed: now })
+                if update {
+                    dir.last_accessed = now;
+                }
             }
+            None => dirs.push(Dir {
+                path: path.into().into(),
+                rank: by.max(0.0),
+                last_accessed: now,
+                pinned: false,
+            }),
         });
-        self.with_dirty_mut(|dirty| *dirty = true);
+    }
+
+    /// Records a change in the journal. If the database is going to be
+    /// rewritten anyway, the journal is skipped.
+    fn log(&mut self, record: &Record) {
+        if !*self.borrow_dirty() {
+            self.with_journal_mut(|journal| journal.push(record));
+        }
+    }
+
+    /// Applies the changes recorded in the journal.
+    fn replay(&mut self, bytes: &[u8]) {
+        for record in Journal::records(bytes) {
+            match record {
+                Ok(Record::Add { path, by, now }) => self.upsert(path, by, now, false),
+                Ok(Record::AddUpdate { path, by, now }) => self.upsert(path, by, now, true),
+                Ok(Record::Learn { keywords, path, now }) => self.associate(keywords, path, now),
+                // A corrupted record means the journal was not written completely.
+                // Drop the rest of it by compacting on the next save.
+                Err(_) => self.mark_dirty(),
+            }
+        }
     }
 
     /// Removes the directory with `path` from the store. This does not preserve
@@ -118,14 +264,224 @@ impl Database {
         self.with_dirty_mut(|dirty| *dirty = true);
     }
 
+    /// Moves `from` and every directory beneath it to the same relative path
+    /// under `to`, as after renaming the directory on disk. Aliases,
+    /// associations and tombstones are moved along with the directories.
+    /// Entries that end up with the same path are merged, as in
+    /// [`Database::dedup`].
+    ///
+    /// Returns the old and new paths of every moved directory.
+    pub fn move_subtree(&mut self, from: &Path, to: &Path) -> Vec<(String, String)> {
+        let moved_path = |path: &str| {
+            let rest = Path::new(path).strip_prefix(from).ok()?;
+            let path = if rest.as_os_str().is_empty() { to.to_path_buf() } else { to.join(rest) };
+            path.into_os_string().into_string().ok()
+        };
+
+        let mut moved = Vec::new();
+        self.with_dirs_mut(|dirs| {
+            for dir in dirs.iter_mut() {
+                if let Some(path) = moved_path(&dir.path) {
+                    moved.push((dir.path.to_string(), path.clone()));
+                    dir.path = path.into();
+                }
+            }
+        });
+        self.with_extra_mut(|extra| {
+            for alias in &mut extra.aliases {
+                if let Some(path) = moved_path(&alias.path) {
+                    alias.path = path;
+                }
+            }
+
+            let associations = mem::take(&mut extra.associations);
+            for mut association in associations {
+                if let Some(path) = moved_path(&association.path) {
+                    association.path = path;
+                }
+                match extra
+                    .associations
+                    .iter_mut()
+                    .find(|a| a.keywords == association.keywords && a.path == association.path)
+                {
+                    Some(a) => {
+                        a.rank += association.rank;
+                        a.last_accessed = a.last_accessed.max(association.last_accessed);
+                    }
+                    None => extra.associations.push(association),
+                }
+            }
+
+            // Tombstones are globs, so the prefix is matched in its escaped form,
+            // and the rest is kept as a glob.
+            let from = from.to_str().map(Pattern::escape);
+            let to = to.to_str().map(Pattern::escape);
+            if let (Some(from), Some(to)) = (from, to) {
+                for tombstone in &mut extra.tombstones {
+                    let Ok(rest) = Path::new(&tombstone.glob).strip_prefix(&from) else {
+                        continue;
+                    };
+                    let glob = if rest.as_os_str().is_empty() {
+                        PathBuf::from(&to)
+                    } else {
+                        Path::new(&to).join(rest)
+                    };
+                    if let Ok(glob) = glob.into_os_string().into_string() {
+                        *tombstone = Tombstone::new(glob);
+                    }
+                }
+            }
+        });
+
+        if !moved.is_empty() {
+            self.with_dirty_mut(|dirty| *dirty = true);
+            self.dedup();
+        }
+        moved
+    }
+
+    /// Pins or unpins the directory with `path`. Returns false if the directory
+    /// is not in the database.
+    pub fn set_pinned(&mut self, path: impl AsRef<str>, pinned: bool) -> bool {
+        let found = self.with_dirs_mut(|dirs| {
+            dirs.iter_mut().find(|dir| dir.path == path.as_ref()).map(|dir| dir.pinned = pinned)
+        });
+        if found.is_some() {
+            self.with_dirty_mut(|dirty| *dirty = true);
+        }
+        found.is_some()
+    }
+
+    pub fn aliases(&self) -> &[Alias] {
+        &self.borrow_extra().aliases
+    }
+
+    pub fn alias(&self, name: &str) -> Option<&Alias> {
+        self.aliases().iter().find(|alias| alias.name == name)
+    }
+
+    /// Creates an alias, or replaces the path of an existing alias with the
+    /// same name.
+    pub fn add_alias(&mut self, name: impl Into<String>, path: impl Into<String>) {
+        let (name, path) = (name.into(), path.into());
+        self.with_extra_mut(|extra| {
+            match extra.aliases.iter_mut().find(|alias| alias.name == name) {
+                Some(alias) => alias.path = path,
+                None => extra.aliases.push(Alias { name, path }),
+            }
+        });
+        self.with_dirty_mut(|dirty| *dirty = true);
+    }
+
+    /// Removes the alias with `name`. Returns false if there is no such alias.
+    pub fn remove_alias(&mut self, name: &str) -> bool {
+        let removed = self.with_extra_mut(|extra| {
+            let len = extra.aliases.len();
+            extra.aliases.retain(|alias| alias.name != name);
+            extra.aliases.len() != len
+        });
+        if removed {
+            self.with_dirty_mut(|dirty| *dirty = true);
+        }
+        removed
+    }
+
+    pub fn associations(&self) -> &[Association] {
+        &self.borrow_extra().associations
+    }
+
+    /// Records that a query with `keywords` led to `path`.
+    pub fn learn(&mut self, keywords: &Keywords, path: impl AsRef<str>, now: Epoch) {
+        if keywords.is_empty() {
+            return;
+        }
+        let keywords = keywords.key();
+        let path = path.as_ref();
+        self.log(&Record::Learn { keywords: keywords.as_str().into(), path: path.into(), now });
+        self.associate(keywords, path, now);
+    }
+
+    fn associate(&mut self, keywords: impl Into<String>, path: impl Into<String>, now: Epoch) {
+        let (keywords, path) = (keywords.into(), path.into());
+        let aged = self.with_extra_mut(|extra| {
+            let associations = &mut extra.associations;
+            match associations.iter_mut().find(|a| a.keywords == keywords && a.path == path) {
+                Some(association) => {
+                    association.rank += 1.0;
+                    association.last_accessed = now;
+                }
+                None => {
+                    associations.push(Association { keywords, path, rank: 1.0, last_accessed: now })
+                }
+            }
+
+            let total = associations.iter().map(|association| association.rank).sum::<Rank>();
+            if total <= Association::MAX_AGE {
+                return false;
+            }
+            let factor = 0.9 * Association::MAX_AGE / total;
+            associations.retain_mut(|association| {
+                association.rank *= factor;
+                association.rank >= 1.0
+            });
+            true
+        });
+        if aged {
+            self.with_dirty_mut(|dirty| *dirty = true);
+        }
+    }
+
+    pub fn tombstones(&self) -> &[Tombstone] {
+        &self.borrow_extra().tombstones
+    }
+
+    /// Returns true if `path` matches a tombstone, and should not be added to
+    /// the database.
+    pub fn is_forgotten(&self, path: &str) -> bool {
+        self.tombstones().iter().any(|tombstone| tombstone.matches(path))
+    }
+
+    /// Removes every directory matching `tombstone` (a glob, or a path that
+    /// only matches itself), and prevents matching directories from being
+    /// added again.
+    pub fn forget(&mut self, tombstone: impl Into<String>) {
+        let tombstone = Tombstone::new(tombstone.into());
+        self.with_dirs_mut(|dirs| dirs.retain(|dir| !tombstone.matches(&dir.path)));
+        self.with_extra_mut(|extra| {
+            if !extra.tombstones.iter().any(|t| t.glob == tombstone.glob) {
+                extra.tombstones.push(tombstone);
+            }
+        });
+        self.with_dirty_mut(|dirty| *dirty = true);
+    }
+
+    /// Removes a tombstone. Returns false if there is no such tombstone.
+    pub fn unforget(&mut self, tombstone: &str) -> bool {
+        let removed = self.with_extra_mut(|extra| {
+            let len = extra.tombstones.len();
+            extra.tombstones.retain(|t| t.glob != tombstone);
+            extra.tombstones.len() != len
+        });
+        if removed {
+            self.with_dirty_mut(|dirty| *dirty = true);
+        }
+        removed
+    }
+
+    /// Scales down the ranks of all directories if their total exceeds
+    /// `max_age`, removing those that fall below 1. Pinned directories are
+    /// neither counted nor aged.
     pub fn age(&mut self, max_age: Rank) {
         let mut dirty = false;
         self.with_dirs_mut(|dirs| {
-            let total_age = dirs.iter().map(|dir| dir.rank).sum::<Rank>();
+            let total_age = dirs.iter().filter(|dir| !dir.pinned).map(|dir| dir.rank).sum::<Rank>();
             if total_age > max_age {
                 let factor = 0.9 * max_age / total_age;
                 for idx in (0..dirs.len()).rev() {
                     let dir = &mut dirs[idx];
+                    if dir.pinned {
+                        continue;
+                    }
                     dir.rank *= factor;
                     if dir.rank < 1.0 {
                         dirs.swap_remove(idx);
@@ -151,11 +507,13 @@ impl Database {
                     continue;
                 }
 
-                // Merge curr_dir's rank and last_accessed into next_dir.
+                // Merge curr_dir's rank, last_accessed and pinned into next_dir.
                 let rank = curr_dir.rank;
                 let last_accessed = curr_dir.last_accessed;
+                let pinned = curr_dir.pinned;
                 let next_dir = &mut dirs[idx - 1];
                 next_dir.last_accessed = next_dir.last_accessed.max(last_accessed);
+                next_dir.pinned |= pinned;
                 next_dir.rank += rank;
 
                 // Delete curr_dir.
@@ -166,74 +524,115 @@ impl Database {
         self.with_dirty_mut(|dirty_prev| *dirty_prev |= dirty);
     }
 
+    /// Sorts the directories by path. The order of entries on disk carries no
+    /// meaning, so this does not mark the database as modified.
     pub fn sort_by_path(&mut self) {
         self.with_dirs_mut(|dirs| dirs.sort_unstable_by(|dir1, dir2| dir1.path.cmp(&dir2.path)));
-        self.with_dirty_mut(|dirty| *dirty = true);
     }
 
+    /// Sorts the directories by score, in ascending order, with pinned
+    /// directories last. Like [`Database::sort_by_path`], this does not mark
+    /// the database as modified.
     pub fn sort_by_score(&mut self, now: Epoch) {
         self.with_dirs_mut(|dirs| {
-            dirs.sort_unstable_by(|dir1: &Dir, dir2: &Dir| {
-                dir1.score(now).total_cmp(&dir2.score(now))
-            })
+            dirs.sort_unstable_by(|dir1, dir2| {
+                dir1.pinned
+                    .cmp(&dir2.pinned)
+                    .then_with(|| dir1.score(now).total_cmp(&dir2.score(now)))
+            });
         });
+    }
+
+    /// Like [`Database::sort_by_score`], but with a custom scoring function.
+    /// The function may be expensive, so it is only called once per directory.
+    pub fn sort_by_score_with(&mut self, score: impl Fn(&Dir) -> Rank) {
+        self.with_dirs_mut(|dirs| {
+            dirs.sort_by_cached_key(|dir| SortKey { pinned: dir.pinned, score: score(dir) });
+        });
+    }
+
+    /// Forces the database to be written on the next save, e.g. to persist the
+    /// current ordering of entries.
+    pub fn mark_dirty(&mut self) {
         self.with_dirty_mut(|dirty| *dirty = true);
     }
 
     pub fn dirty(&self) -> bool {
-        *self.borrow_dirty()
+        *self.borrow_dirty() || self.borrow_journal().has_pending()
     }
 
     pub fn dirs(&self) -> &[Dir<'_>] {
         self.borrow_dirs()
     }
 
-    fn serialize(dirs: &[Dir<'_>]) -> Result<Vec<u8>> {
-        (|| -> bincode::Result<_> {
-            // Preallocate buffer with combined size of sections.
-            let buffer_size =
-                bincode::serialized_size(&Self::VERSION)? + bincode::serialized_size(&dirs)?;
-            let mut buffer = Vec::with_capacity(buffer_size as usize);
+    /// Size of the database file when it was opened, in bytes. This does not
+    /// include the journal.
+    pub fn size(&self) -> u64 {
+        self.borrow_bytes().len() as u64
+    }
 
-            // Serialize sections into buffer.
-            bincode::serialize_into(&mut buffer, &Self::VERSION)?;
-            bincode::serialize_into(&mut buffer, &dirs)?;
+    /// Acquires an exclusive advisory lock for the database at `path`. The lock
+    /// is taken on a separate file, since the database itself is atomically
+    /// replaced on every save.
+    fn lock(path: &Path) -> Result<Option<File>> {
+        let lock_path = Self::sibling(path, ".lock");
+
+        let file = OpenOptions::new()
+            .create(true)
+            .truncate(false)
+            .write(true)
+            .open(&lock_path)
+            .with_context(|| format!("could not open lock file: {}", lock_path.display()))?;
+        match file.lock() {
+            Ok(()) => Ok(Some(file)),
+            // Some filesystems don't support locking. Fall back to the previous
+            // behaviour of unsynchronized writes, rather than failing entirely.
+            Err(e) if e.kind() == io::ErrorKind::Unsupported => Ok(None),
+            Err(e) => {
+                Err(e).with_context(|| format!("could not lock database: {}", lock_path.display()))
+            }
+        }
+    }
 
-            Ok(buffer)
-        })()
-        .context("could not serialize database")
+    /// Returns the path of a file stored alongside the database, e.g.
+    /// `db.zo.lock`.
+    fn sibling(path: &Path, suffix: &str) -> PathBuf {
+        let mut path = path.as_os_str().to_owned();
+        path.push(suffix);
+        path.into()
     }
+}
 
-    fn deserialize(bytes: &[u8]) -> Result<Vec<Dir<'_>>> {
-        // Assume a maximum size for the database. This prevents bincode from throwing
-        // strange errors when it encounters invalid data.
-        const MAX_SIZE: u64 = 32 << 20; // 32 MiB
-        let deserializer = &mut bincode::options().with_fixint_encoding().with_limit(MAX_SIZE);
+/// Orders directories by score, with pinned directories last.
+struct SortKey {
+    pinned: bool,
+    score: Rank,
+}
 
-        // Split bytes into sections.
-        let version_size = deserializer.serialized_size(&Self::VERSION).unwrap() as _;
-        if bytes.len() < version_size {
-            bail!("could not deserialize database: corrupted data");
-        }
-        let (bytes_version, bytes_dirs) = bytes.split_at(version_size);
+impl Ord for SortKey {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.pinned.cmp(&other.pinned).then_with(|| self.score.total_cmp(&other.score))
+    }
+}
 
-        // Deserialize sections.
-        let version = deserializer.deserialize(bytes_version)?;
-        let dirs = match version {
-            Self::VERSION => {
-                deserializer.deserialize(bytes_dirs).context("could not deserialize database")?
-            }
-            version => {
-                bail!("unsupported version (got {version}, supports {})", Self::VERSION)
-            }
-        };
+impl PartialOrd for SortKey {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
 
-        Ok(dirs)
+impl PartialEq for SortKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other).is_eq()
     }
 }
 
+impl Eq for SortKey {}
+
 #[cfg(test)]
 mod tests {
+    use std::path::MAIN_SEPARATOR;
+
     use super::*;
 
     #[test]
@@ -260,6 +659,31 @@ mod tests {
         }
     }
 
+    #[test]
+    fn read_only() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let path = if cfg!(windows) { r"C:\foo\bar" } else { "/foo/bar" };
+        let now = 946684800;
+
+        {
+            let mut db = Database::open_dir(data_dir.path()).unwrap();
+            db.add(path, 1.0, now);
+            db.save().unwrap();
+        }
+
+        // Readers don't wait for the lock.
+        let _locked = Database::open_dir(data_dir.path()).unwrap();
+        let db = Database::read(Database::path(data_dir.path()), None).unwrap();
+        assert_eq!(db.dirs().len(), 1);
+        assert_eq!(db.dirs()[0].path, path);
+
+        // A missing data directory is read as empty, and isn't created.
+        let missing = data_dir.path().join("missing");
+        let db = Database::read(Database::path(&missing), None).unwrap();
+        assert!(db.dirs().is_empty());
+        assert!(!missing.exists());
+    }
+
     #[test]
     fn remove() {
         let data_dir = tempfile::tempdir().unwrap();
@@ -285,4 +709,271 @@ mod tests {
             db.save().unwrap();
         }
     }
+
+    #[test]
+    fn pinned() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let (foo, bar) = if cfg!(windows) { (r"C:\foo", r"C:\bar") } else { ("/foo", "/bar") };
+        let now = 946684800;
+
+        {
+            let mut db = Database::open_dir(data_dir.path()).unwrap();
+            db.add(foo, 1.0, now);
+            db.add(bar, 10.0, now);
+            assert!(db.set_pinned(foo, true));
+            db.save().unwrap();
+        }
+
+        {
+            let mut db = Database::open_dir(data_dir.path()).unwrap();
+
+            // Pinned directories are ranked first.
+            db.sort_by_score(now);
+            let dirs = db.dirs().iter().rev().map(|dir| (dir.path.as_ref(), dir.pinned));
+            assert_eq!(dirs.collect::<Vec<_>>(), [(foo, true), (bar, false)]);
+
+            // Pinned directories are never aged out.
+            db.age(1.0);
+            assert_eq!(db.dirs().len(), 1);
+            assert_eq!(db.dirs()[0].path, foo);
+            assert!((db.dirs()[0].rank - 1.0).abs() < 0.01);
+        }
+    }
+
+    #[test]
+    fn sort_by_score_with() {
+        let (foo, bar, baz) = if cfg!(windows) {
+            (r"C:\foo", r"C:\bar", r"C:\baz")
+        } else {
+            ("/foo", "/bar", "/baz")
+        };
+        let mut db = Database::from_dirs(Vec::new());
+        db.add(foo, 1.0, 0);
+        db.add(bar, 2.0, 0);
+        db.add(baz, 3.0, 0);
+        db.set_pinned(foo, true);
+
+        // Each directory is scored once, and pinned directories sort last.
+        let calls = std::cell::Cell::new(0);
+        db.sort_by_score_with(|dir| {
+            calls.set(calls.get() + 1);
+            -dir.rank
+        });
+        assert_eq!(calls.get(), 3);
+        let paths = db.dirs().iter().map(|dir| dir.path.as_ref()).collect::<Vec<_>>();
+        assert_eq!(paths, [baz, bar, foo]);
+    }
+
+    #[test]
+    fn alias() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let (foo, bar) = if cfg!(windows) { (r"C:\foo", r"C:\bar") } else { ("/foo", "/bar") };
+
+        {
+            let mut db = Database::open_dir(data_dir.path()).unwrap();
+            db.add_alias("foo", foo);
+            db.add_alias("bar", foo);
+            db.add_alias("bar", bar);
+            db.save().unwrap();
+        }
+
+        {
+            let mut db = Database::open_dir(data_dir.path()).unwrap();
+            assert_eq!(db.aliases().len(), 2);
+            assert_eq!(db.alias("bar").unwrap().path, bar);
+            assert!(db.remove_alias("foo"));
+            assert!(!db.remove_alias("foo"));
+            db.save().unwrap();
+        }
+
+        let db = Database::open_dir(data_dir.path()).unwrap();
+        assert!(db.alias("foo").is_none());
+        assert!(db.dirs().is_empty());
+    }
+
+    #[test]
+    fn forget() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let (foo, bar, glob) = if cfg!(windows) {
+            (r"C:\foo", r"C:\tmp\bar", r"C:\tmp\*")
+        } else {
+            ("/foo", "/tmp/bar", "/tmp/*")
+        };
+
+        {
+            let mut db = Database::open_dir(data_dir.path()).unwrap();
+            db.add(foo, 1.0, 0);
+            db.add(bar, 1.0, 0);
+            db.forget(foo);
+            db.forget(glob);
+            assert!(db.dirs().is_empty());
+            db.save().unwrap();
+        }
+
+        let mut db = Database::open_dir(data_dir.path()).unwrap();
+        assert!(db.is_forgotten(foo));
+        assert!(db.is_forgotten(bar));
+        assert!(db.unforget(glob));
+        assert!(!db.unforget(glob));
+        assert!(!db.is_forgotten(bar));
+    }
+
+    #[test]
+    fn move_subtree() {
+        let (old, old_sub, new, new_sub, other) = if cfg!(windows) {
+            (r"C:\old", r"C:\old\sub", r"C:\new", r"C:\new\sub", r"C:\older")
+        } else {
+            ("/old", "/old/sub", "/new", "/new/sub", "/older")
+        };
+
+        let mut db = Database::from_dirs(Vec::new());
+        db.add(old, 1.0, 10);
+        db.add(old_sub, 2.0, 20);
+        db.add(new_sub, 3.0, 30);
+        db.add(other, 1.0, 0);
+        db.add_alias("sub", old_sub);
+        db.forget(Pattern::escape(&format!("{old_sub}{MAIN_SEPARATOR}[1]")));
+        db.forget(format!("{old}{MAIN_SEPARATOR}*.tmp"));
+        db.forget(format!("{other}{MAIN_SEPARATOR}*"));
+
+        let moved = db.move_subtree(Path::new(old), Path::new(new));
+        assert_eq!(moved.len(), 2);
+
+        db.sort_by_path();
+        let dirs = db.dirs().iter().map(|dir| (&*dir.path, dir.rank, dir.last_accessed));
+        assert_eq!(dirs.collect::<Vec<_>>(), [(new, 1.0, 10), (new_sub, 5.0, 30), (other, 1.0, 0)]);
+        assert_eq!(db.alias("sub").unwrap().path, new_sub);
+
+        // Tombstones under the old path are moved, and still match literally.
+        let tombstones = db.tombstones().iter().map(|t| t.glob.as_str()).collect::<Vec<_>>();
+        assert_eq!(
+            tombstones,
+            [
+                Pattern::escape(&format!("{new_sub}{MAIN_SEPARATOR}[1]")),
+                format!("{new}{MAIN_SEPARATOR}*.tmp"),
+                format!("{other}{MAIN_SEPARATOR}*"),
+            ]
+        );
+        assert!(db.is_forgotten(&format!("{new_sub}{MAIN_SEPARATOR}[1]")));
+        assert!(!db.is_forgotten(&format!("{new_sub}{MAIN_SEPARATOR}1")));
+    }
+
+    #[test]
+    fn journal() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let db_path = data_dir.path().join("db.zo");
+        let journal_path = data_dir.path().join("db.zo.journal");
+        let path = if cfg!(windows) { r"C:\foo\bar" } else { "/foo/bar" };
+        let now = 946684800;
+
+        // Adding entries should only append to the journal.
+        for _ in 0..2 {
+            let mut db = Database::open_dir(data_dir.path()).unwrap();
+            db.add_update(path, 1.0, now);
+            db.save().unwrap();
+        }
+        assert!(!db_path.exists());
+        assert!(journal_path.exists());
+
+        // Opening the database should replay the journal.
+        {
+            let mut db = Database::open_dir(data_dir.path()).unwrap();
+            assert_eq!(db.dirs().len(), 1);
+            assert!((db.dirs()[0].rank - 2.0).abs() < 0.01);
+
+            // Any other change should compact the journal into the database.
+            db.age(1.0);
+            db.save().unwrap();
+        }
+        assert!(db_path.exists());
+        assert!(!journal_path.exists());
+    }
+
+    #[test]
+    fn learn() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let path = if cfg!(windows) { r"C:\work\api-gateway" } else { "/work/api-gateway" };
+        let now = 946684800;
+
+        for _ in 0..2 {
+            let mut db = Database::open_dir(data_dir.path()).unwrap();
+            let keywords = Keywords::new(&["GW"], Matching::Substring, false).unwrap();
+            db.learn(&keywords, path, now);
+            let keywords = Keywords::new(&[] as &[&str], Matching::Substring, false).unwrap();
+            db.learn(&keywords, path, now);
+            db.save().unwrap();
+        }
+
+        let mut db = Database::open_dir(data_dir.path()).unwrap();
+        let associations = db.associations();
+        assert_eq!(associations.len(), 1);
+        assert_eq!(associations[0].keywords, "gw");
+        assert_eq!(associations[0].rank, 2.0);
+
+        // Keywords that are matched case-sensitively are stored as-is.
+        let keywords = Keywords::new(&["GW"], Matching::Substring, true).unwrap();
+        db.learn(&keywords, path, now);
+        assert!(db.associations().iter().any(|association| association.keywords == "GW"));
+
+        // Associations are aged separately from directories.
+        for idx in 0..Association::MAX_AGE as usize {
+            let keywords = Keywords::new(&[idx.to_string()], Matching::Substring, false).unwrap();
+            db.learn(&keywords, path, now);
+        }
+        assert!(db.associations().len() < Association::MAX_AGE as usize);
+        assert!(db.associations().iter().map(|a| a.rank).sum::<Rank>() <= Association::MAX_AGE);
+    }
+
+    #[test]
+    fn journal_corrupted() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let journal_path = data_dir.path().join("db.zo.journal");
+        let path = if cfg!(windows) { r"C:\foo\bar" } else { "/foo/bar" };
+        let now = 946684800;
+
+        {
+            let mut db = Database::open_dir(data_dir.path()).unwrap();
+            db.add_update(path, 1.0, now);
+            db.save().unwrap();
+        }
+
+        // Simulate an interrupted append.
+        let mut bytes = fs::read(&journal_path).unwrap();
+        bytes.extend_from_slice(&bytes.clone()[..5]);
+        fs::write(&journal_path, bytes).unwrap();
+
+        let mut db = Database::open_dir(data_dir.path()).unwrap();
+        assert_eq!(db.dirs().len(), 1);
+        assert!(db.dirty());
+        db.save().unwrap();
+        assert!(!journal_path.exists());
+    }
+
+    #[test]
+    fn concurrent_add() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let now = 946684800;
+
+        let handles = (0..8)
+            .map(|idx| {
+                let data_dir = data_dir.path().to_path_buf();
+                std::thread::spawn(move || {
+                    let path = if cfg!(windows) {
+                        format!(r"C:\foo\{idx}")
+                    } else {
+                        format!("/foo/{idx}")
+                    };
+                    let mut db = Database::open_dir(data_dir).unwrap();
+                    db.add_update(path, 1.0, now);
+                    db.save().unwrap();
+                })
+            })
+            .collect::<Vec<_>>();
+        for handle in handles {
+            handle.join().unwrap();
+        }
+
+        let db = Database::open_dir(data_dir.path()).unwrap();
+        assert_eq!(db.dirs().len(), 8);
+    }
 }
@@ -0,0 +1,60 @@
+use std::path::{Component, Path, PathBuf};
+
+use crate::db::Rank;
+
+/// Boosts directories that are close to the current directory, so that
+/// queries prefer matches within the project being worked on.
+pub struct Proximity {
+    base: PathBuf,
+    weight: Rank,
+    repository: bool,
+}
+
+impl Proximity {
+    /// Boosts directories by how many leading components they share with
+    /// `dir`.
+    pub fn directory(dir: impl Into<PathBuf>, weight: Rank) -> Self {
+        Proximity { base: dir.into(), weight, repository: false }
+    }
+
+    /// Boosts directories within the repository at `root`.
+    pub fn repository(root: impl Into<PathBuf>, weight: Rank) -> Self {
+        Proximity { base: root.into(), weight, repository: true }
+    }
+
+    /// Uses the enclosing git repository if there is one, or `current_dir`
+    /// itself otherwise.
+    pub fn from_current_dir(current_dir: &Path, weight: Rank) -> Self {
+        match current_dir.ancestors().find(|dir| dir.join(".git").exists()) {
+            Some(root) => Proximity::repository(root, weight),
+            None => Proximity::directory(current_dir, weight),
+        }
+    }
+
+    /// Returns the factor by which the score of `path` is multiplied, up to
+    /// `1 + weight` for the base and everything inside it.
+    pub fn boost(&self, path: &str) -> Rank {
+        let is_normal = |component: &Component| matches!(component, Component::Normal(_));
+        let total = self.base.components().filter(is_normal).count();
+        if total == 0 {
+            return 1.0;
+        }
+        let shared = Path::new(path)
+            .components()
+            .zip(self.base.components())
+            .take_while(|(a, b)| a == b)
+            .filter(|(component, _)| is_normal(component))
+            .count();
+
+        // Directories elsewhere in the same parent as a repository are usually
+        // unrelated to it, so only directories inside it are boosted.
+        let closeness = if shared == total {
+            1.0
+        } else if self.repository {
+            0.0
+        } else {
+            shared as Rank / total as Rank
+        };
+        1.0 + self.weight * closeness
+    }
+}
@@ -0,0 +1,285 @@
+//! The on-disk format of the database, and migrations between its versions.
+//!
+//! Version 3 stores a bare list of directories. From version 4 onwards, the
+//! database is a list of named sections. New kinds of data are added as new
+//! sections rather than by changing existing ones, so a release that can read
+//! version 4 carries over sections it doesn't recognize when rewriting the
+//! database, instead of being locked out of it. The version only needs to be
+//! bumped for changes that older releases can't safely ignore.
+
+use std::collections::HashSet;
+
+use anyhow::{Context, Result, bail};
+use bincode::Options;
+use serde::{Deserialize, Serialize};
+
+use crate::db::{Alias, Association, Database, Dir, Tombstone};
+
+/// The version written by this release.
+pub const VERSION: u32 = 4;
+
+/// The oldest version that can be read, and written by `zoxide migrate`.
+pub const VERSION_MIN: u32 = 3;
+
+/// Assume a maximum size for the database. This prevents bincode from throwing
+/// strange errors when it encounters invalid data.
+pub const MAX_SIZE: u64 = 32 << 20; // 32 MiB
+
+const SECTION_ALIASES: &str = "aliases";
+const SECTION_ASSOCIATIONS: &str = "associations";
+const SECTION_DIRS: &str = "dirs";
+const SECTION_PINS: &str = "pins";
+const SECTION_TOMBSTONES: &str = "tombstones";
+
+/// A section that this release doesn't know how to interpret. It is stored
+/// as-is so that it can be written back unchanged.
+#[derive(Clone, Debug)]
+pub struct Section {
+    pub name: String,
+    pub data: Vec<u8>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct RawSection<'a> {
+    name: &'a str,
+    data: &'a [u8],
+}
+
+/// Data stored in the database besides the list of directories.
+#[derive(Debug, Default)]
+pub struct Extra {
+    pub aliases: Vec<Alias>,
+
+    /// Keywords that previously led to each directory.
+    pub associations: Vec<Association>,
+
+    /// Paths or globs of directories that must not be added again.
+    pub tombstones: Vec<Tombstone>,
+
+    /// Sections that this release doesn't know how to interpret.
+    pub sections: Vec<Section>,
+}
+
+/// The contents of a database file, upgraded to the current version.
+pub struct Contents<'a> {
+    pub dirs: Vec<Dir<'a>>,
+    pub extra: Extra,
+
+    /// The version the contents were read from.
+    pub version: u32,
+}
+
+pub fn serialize(db: &Database, version: u32) -> Result<Vec<u8>> {
+    (|| -> Result<_> {
+        let mut buffer = Vec::new();
+        bincode::serialize_into(&mut buffer, &version)?;
+
+        match version {
+            3 => bincode::serialize_into(&mut buffer, db.dirs())?,
+            4 => {
+                let dirs = bincode::serialize(db.dirs())?;
+                let pins = db.dirs().iter().filter(|dir| dir.pinned).map(|dir| &dir.path);
+                let pins = bincode::serialize(&pins.collect::<Vec<_>>())?;
+                let aliases = bincode::serialize(db.aliases())?;
+                let associations = bincode::serialize(db.associations())?;
+                let tombstones = bincode::serialize(db.tombstones())?;
+
+                let mut sections = vec![
+                    RawSection { name: SECTION_ALIASES, data: &aliases },
+                    RawSection { name: SECTION_ASSOCIATIONS, data: &associations },
+                    RawSection { name: SECTION_DIRS, data: &dirs },
+                    RawSection { name: SECTION_PINS, data: &pins },
+                    RawSection { name: SECTION_TOMBSTONES, data: &tombstones },
+                ];
+                sections.extend(
+                    db.borrow_extra()
+                        .sections
+                        .iter()
+                        .map(|section| RawSection { name: &section.name, data: &section.data }),
+                );
+                bincode::serialize_into(&mut buffer, &sections)?;
+            }
+            version => bail!("unsupported version: {version}"),
+        }
+
+        Ok(buffer)
+    })()
+    .context("could not serialize database")
+}
+
+/// Returns the names of the sections that would be discarded when writing the
+/// database in the format of `version`.
+pub fn unsupported(db: &Database, version: u32) -> Vec<String> {
+    if version >= 4 {
+        return Vec::new();
+    }
+
+    let mut names = Vec::new();
+    if !db.aliases().is_empty() {
+        names.push(SECTION_ALIASES.to_string());
+    }
+    if !db.associations().is_empty() {
+        names.push(SECTION_ASSOCIATIONS.to_string());
+    }
+    if db.dirs().iter().any(|dir| dir.pinned) {
+        names.push(SECTION_PINS.to_string());
+    }
+    if !db.tombstones().is_empty() {
+        names.push(SECTION_TOMBSTONES.to_string());
+    }
+    names.extend(db.borrow_extra().sections.iter().map(|section| section.name.clone()));
+    names
+}
+
+pub fn deserialize(bytes: &[u8]) -> Result<Contents<'_>> {
+    let deserializer = &mut options();
+
+    // Split bytes into sections.
+    let version_size = deserializer.serialized_size(&VERSION).unwrap() as _;
+    if bytes.len() < version_size {
+        bail!("could not deserialize database: corrupted data");
+    }
+    let (bytes_version, bytes_body) = bytes.split_at(version_size);
+
+    // Deserialize sections.
+    let version = deserializer.deserialize(bytes_version)?;
+    let mut contents = Contents { dirs: Vec::new(), extra: Extra::default(), version };
+    match version {
+        3 => {
+            contents.dirs =
+                deserializer.deserialize(bytes_body).context("could not deserialize database")?;
+        }
+        4 => {
+            let sections: Vec<RawSection> =
+                deserializer.deserialize(bytes_body).context("could not deserialize database")?;
+            let mut pins = HashSet::<&str>::new();
+            for section in sections {
+                match section.name {
+                    SECTION_ALIASES => {
+                        contents.extra.aliases = deserializer
+                            .deserialize(section.data)
+                            .context("could not deserialize aliases")?;
+                    }
+                    SECTION_ASSOCIATIONS => {
+                        contents.extra.associations = deserializer
+                            .deserialize(section.data)
+                            .context("could not deserialize associations")?;
+                    }
+                    SECTION_DIRS => {
+                        contents.dirs = deserializer
+                            .deserialize(section.data)
+                            .context("could not deserialize database")?;
+                    }
+                    SECTION_TOMBSTONES => {
+                        contents.extra.tombstones = deserializer
+                            .deserialize(section.data)
+                            .context("could not deserialize tombstones")?;
+                    }
+                    SECTION_PINS => {
+                        pins = deserializer
+                            .deserialize(section.data)
+                            .context("could not deserialize pinned directories")?;
+                    }
+                    name => contents
+                        .extra
+                        .sections
+                        .push(Section { name: name.to_string(), data: section.data.to_vec() }),
+                }
+            }
+            for dir in &mut contents.dirs {
+                dir.pinned = pins.contains(dir.path.as_ref());
+            }
+        }
+        version if version > VERSION => bail!(
+            "database was created by a newer version of zoxide (got version {version}, supports \
+             up to {VERSION}), please upgrade zoxide or run `zoxide migrate --to {VERSION}` with \
+             the newer release"
+        ),
+        version => {
+            bail!("unsupported version (got {version}, supports {VERSION_MIN} to {VERSION})")
+        }
+    }
+
+    Ok(contents)
+}
+
+fn options() -> impl Options {
+    bincode::options().with_fixint_encoding().with_limit(MAX_SIZE)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    fn write_version(path: &std::path::Path, version: u32, body: &impl Serialize) {
+        let mut bytes = bincode::serialize(&version).unwrap();
+        bytes.extend(bincode::serialize(body).unwrap());
+        fs::write(path, bytes).unwrap();
+    }
+
+    fn read_version(path: &std::path::Path) -> u32 {
+        bincode::deserialize(&fs::read(path).unwrap()).unwrap()
+    }
+
+    #[test]
+    fn upgrade() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let db_path = data_dir.path().join("db.zo");
+        let dirs =
+            vec![Dir { path: "/foo/bar".into(), rank: 1.0, last_accessed: 0, pinned: false }];
+        write_version(&db_path, 3, &dirs);
+
+        {
+            let mut db = Database::open_dir(data_dir.path()).unwrap();
+            assert_eq!(db.dirs().len(), 1);
+            assert!(db.dirty());
+            db.save().unwrap();
+        }
+        assert_eq!(read_version(&db_path), VERSION);
+
+        {
+            let mut db = Database::open_dir(data_dir.path()).unwrap();
+            assert_eq!(db.dirs().len(), 1);
+            assert!(!db.dirty());
+            assert!(db.save_version(3).unwrap().is_empty());
+        }
+        assert_eq!(read_version(&db_path), 3);
+    }
+
+    #[test]
+    fn newer_version() {
+        let data_dir = tempfile::tempdir().unwrap();
+        write_version(&data_dir.path().join("db.zo"), VERSION + 1, &());
+
+        let err = Database::open_dir(data_dir.path()).err().unwrap();
+        assert!(err.to_string().contains("newer version of zoxide"));
+    }
+
+    #[test]
+    fn unknown_sections() {
+        let data_dir = tempfile::tempdir().unwrap();
+        let db_path = data_dir.path().join("db.zo");
+        let dirs = bincode::serialize(&Vec::<Dir>::new()).unwrap();
+        let sections = vec![
+            RawSection { name: SECTION_DIRS, data: &dirs },
+            RawSection { name: "unknown", data: b"data" },
+        ];
+        write_version(&db_path, 4, &sections);
+
+        {
+            let mut db = Database::open_dir(data_dir.path()).unwrap();
+            db.add_unchecked("/foo/bar", 1.0, 0, false);
+            db.save().unwrap();
+        }
+
+        let mut db = Database::open_dir(data_dir.path()).unwrap();
+        assert_eq!(db.dirs().len(), 1);
+        let section = &db.borrow_extra().sections[0];
+        assert_eq!((section.name.as_str(), section.data.as_slice()), ("unknown", &b"data"[..]));
+
+        // Downgrading discards sections unknown to the older version.
+        assert_eq!(db.save_version(3).unwrap(), ["unknown"]);
+    }
+}
@@ -1,53 +1,120 @@
+use std::collections::HashMap;
+use std::fs;
 use std::iter::Rev;
 use std::ops::Range;
 use std::path::Path;
-use std::{fs, path};
 
+use anyhow::Result;
 use glob::Pattern;
 
-use crate::db::{Database, Dir, Epoch};
-use crate::util::{self, MONTH};
+use crate::db::dir;
+use crate::db::keyword::{Keywords, Matching};
+use crate::db::{Database, Dir, Epoch, Proximity, Rank};
+use crate::util::MONTH;
 
 pub struct Stream<'a> {
     db: &'a mut Database,
     idxs: Rev<Range<usize>>,
     options: StreamOptions,
+    scorer: Scorer,
 }
 
 impl<'a> Stream<'a> {
-    pub fn new(db: &'a mut Database, options: StreamOptions) -> Self {
-        db.sort_by_score(options.now);
+    /// Fails if the keywords are invalid, e.g. a malformed regex.
+    pub fn new(db: &'a mut Database, mut options: StreamOptions) -> Result<Self> {
+        let keywords = Keywords::new(&options.keywords, options.matching, options.smart_case)?;
+        let key = keywords.key();
+        let boosts = db
+            .associations()
+            .iter()
+            .filter(|association| !keywords.is_empty() && association.keywords == key)
+            .map(|association| (association.path.clone(), association.boost(options.now)))
+            .collect::<HashMap<_, _>>();
+
+        let proximity = options.proximity.take();
+        let scorer = Scorer { now: options.now, keywords, boosts, proximity };
+        if options.matching == Matching::Fuzzy
+            || !scorer.boosts.is_empty()
+            || scorer.proximity.is_some()
+        {
+            db.sort_by_score_with(|dir| scorer.score(dir));
+        } else {
+            db.sort_by_score(options.now);
+        }
         let idxs = (0..db.dirs().len()).rev();
-        Stream { db, idxs, options }
+        Ok(Stream { db, idxs, options, scorer })
     }
 
-    pub fn next(&mut self) -> Option<&Dir<'_>> {
+    /// Returns the next matching directory, along with its score.
+    pub fn next(&mut self) -> Option<(&Dir<'_>, Rank)> {
         while let Some(idx) = self.idxs.next() {
-            let dir = &self.db.dirs()[idx];
-
-            if !self.filter_by_keywords(&dir.path) {
+            let Some(score) = self.scorer.score_match(&self.db.dirs()[idx]) else {
                 continue;
-            }
-
-            if !self.filter_by_base_dir(&dir.path) {
+            };
+            if self.filter(idx).is_some() {
                 continue;
             }
+            return Some((&self.db.dirs()[idx], score));
+        }
 
-            if !self.filter_by_exclude(&dir.path) {
-                self.db.swap_remove(idx);
-                continue;
-            }
+        None
+    }
 
-            // Exists queries are slow, this should always be checked last.
-            if !self.filter_by_exists(&dir.path) {
-                if dir.last_accessed < self.options.ttl {
-                    self.db.swap_remove(idx);
-                }
-                continue;
+    /// Like [`Stream::next`], but also returns directories that matched the
+    /// keywords and were then filtered out, along with how each one was
+    /// scored.
+    pub fn next_explained(&mut self) -> Option<Explanation> {
+        let idx = loop {
+            let idx = self.idxs.next()?;
+            if self.filter_by_keywords(&self.db.dirs()[idx].path) {
+                break idx;
             }
+        };
 
-            let dir = &self.db.dirs()[idx];
-            return Some(dir);
+        let dir = &self.db.dirs()[idx];
+        let (age, age_factor) = dir::recency(dir.last_accessed, self.options.now);
+        let (quality, learned, proximity) = self.scorer.factors(dir);
+        let explanation = Explanation {
+            dir: Dir {
+                path: dir.path.to_string().into(),
+                rank: dir.rank,
+                last_accessed: dir.last_accessed,
+                pinned: dir.pinned,
+            },
+            score: self.scorer.scale(dir, quality, learned, proximity),
+            age,
+            age_factor,
+            quality,
+            learned,
+            proximity,
+            matches: self.scorer.keywords.matches(&dir.path).unwrap_or_default(),
+            filtered: None,
+        };
+        Some(Explanation { filtered: self.filter(idx), ..explanation })
+    }
+
+    /// Checks a directory that matched the keywords against the remaining
+    /// filters, lazily removing it from the database if needed. Returns why it
+    /// was filtered out, if it was.
+    fn filter(&mut self, idx: usize) -> Option<Filtered> {
+        let dir = &self.db.dirs()[idx];
+
+        if !self.filter_by_base_dir(&dir.path) {
+            return Some(Filtered::BaseDir);
+        }
+
+        if !self.filter_by_exclude(&dir.path) {
+            self.db.swap_remove(idx);
+            return Some(Filtered::Exclude);
+        }
+
+        // Exists queries are slow, this should always be checked last.
+        if !self.filter_by_exists(&dir.path) {
+            if dir.last_accessed < self.options.ttl && !dir.pinned {
+                self.db.swap_remove(idx);
+                return Some(Filtered::Pruned);
+            }
+            return Some(Filtered::Missing);
         }
 
         None
@@ -77,35 +144,100 @@ impl<'a> Stream<'a> {
         resolver(path).map(|metadata| metadata.is_dir()).unwrap_or_default()
     }
 
-    fn filter_by_keywords(&self, path: &str) -> bool {
-        let (keywords_last, keywords) = match self.options.keywords.split_last() {
-            Some(split) => split,
-            None => return true,
-        };
+    pub fn filter_by_keywords(&self, path: &str) -> bool {
+        self.scorer.keywords.is_match(path)
+    }
+}
 
-        let path = util::to_lowercase(path);
-        let mut path = path.as_str();
-        match path.rfind(keywords_last) {
-            Some(idx) => {
-                if path[idx + keywords_last.len()..].contains(path::is_separator) {
-                    return false;
-                }
-                path = &path[..idx];
-            }
-            None => return false,
-        }
+struct Scorer {
+    now: Epoch,
+    keywords: Keywords,
 
-        for keyword in keywords.iter().rev() {
-            match path.rfind(keyword) {
-                Some(idx) => path = &path[..idx],
-                None => return false,
-            }
-        }
+    /// Boosts for directories that the same keywords have led to before.
+    boosts: HashMap<String, Rank>,
+
+    /// Boosts directories close to the current directory.
+    proximity: Option<Proximity>,
+}
+
+impl Scorer {
+    /// Returns the score used to rank `dir`. This is the frecency of the
+    /// directory, scaled by how well it matches the keywords in fuzzy mode,
+    /// by how often the same keywords have led to it, and by how close it is
+    /// to the current directory.
+    fn score(&self, dir: &Dir) -> Rank {
+        let (quality, learned, proximity) = self.factors(dir);
+        self.scale(dir, quality, learned, proximity)
+    }
+
+    /// Like [`Scorer::score`], but returns `None` if `dir` doesn't match the
+    /// keywords, which saves matching it separately.
+    fn score_match(&self, dir: &Dir) -> Option<Rank> {
+        let quality = self.keywords.grade(&dir.path)?;
+        let (learned, proximity) = self.boosts(dir);
+        Some(self.scale(dir, quality, learned, proximity))
+    }
+
+    fn scale(&self, dir: &Dir, quality: f64, learned: Rank, proximity: Rank) -> Rank {
+        dir.score(self.now) * quality * learned * proximity
+    }
+
+    /// Returns the factors that the frecency of `dir` is scaled by.
+    fn factors(&self, dir: &Dir) -> (f64, Rank, Rank) {
+        let quality = self.keywords.quality(&dir.path).unwrap_or(0.0);
+        let (learned, proximity) = self.boosts(dir);
+        (quality, learned, proximity)
+    }
 
-        true
+    /// Returns the boosts from previous jumps and from proximity.
+    fn boosts(&self, dir: &Dir) -> (Rank, Rank) {
+        let learned = self.boosts.get(dir.path.as_ref()).copied().unwrap_or(1.0);
+        let proximity = self.proximity.as_ref().map_or(1.0, |proximity| proximity.boost(&dir.path));
+        (learned, proximity)
     }
 }
 
+/// How a directory was scored by [`Stream::next_explained`], and whether it
+/// was filtered out.
+pub struct Explanation {
+    pub dir: Dir<'static>,
+    pub score: Rank,
+
+    /// The age bucket of the directory, and the multiplier for its rank.
+    pub age: &'static str,
+    pub age_factor: Rank,
+
+    /// How well the keywords matched, from 0 to 1. This is always 1 unless
+    /// matching is fuzzy.
+    pub quality: f64,
+
+    /// The boost from previous jumps with the same keywords.
+    pub learned: Rank,
+
+    /// The boost from being close to the current directory.
+    pub proximity: Rank,
+
+    /// The byte range of the path matched by each keyword. This is empty for
+    /// fuzzy matches.
+    pub matches: Vec<Range<usize>>,
+
+    pub filtered: Option<Filtered>,
+}
+
+/// Why a directory that matched the keywords was not returned.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Filtered {
+    /// It is outside the base directory.
+    BaseDir,
+    /// It matches an exclude glob, and was removed from the database.
+    Exclude,
+    /// It doesn't exist on the filesystem.
+    Missing,
+    /// It doesn't exist on the filesystem and hasn't been accessed in a while,
+    /// so it was removed from the database.
+    Pruned,
+}
+
 pub struct StreamOptions {
     /// The current time.
     now: Epoch,
@@ -113,6 +245,12 @@ pub struct StreamOptions {
     /// Only directories matching these keywords will be returned.
     keywords: Vec<String>,
 
+    /// How keywords are matched against paths.
+    matching: Matching,
+
+    /// Match keywords that contain uppercase letters case-sensitively.
+    smart_case: bool,
+
     /// Directories that match any of these globs will be lazily removed.
     exclude: Vec<Pattern>,
 
@@ -129,6 +267,9 @@ pub struct StreamOptions {
     /// Only return directories within this parent directory
     /// Does not check if the path exists
     base_dir: Option<String>,
+
+    /// Boosts directories close to the current directory.
+    proximity: Option<Proximity>,
 }
 
 impl StreamOptions {
@@ -136,11 +277,14 @@ impl StreamOptions {
         StreamOptions {
             now,
             keywords: Vec::new(),
+            matching: Matching::default(),
+            smart_case: false,
             exclude: Vec::new(),
             exists: false,
             resolve_symlinks: false,
             ttl: now.saturating_sub(3 * MONTH),
             base_dir: None,
+            proximity: None,
         }
     }
 
@@ -149,7 +293,17 @@ impl StreamOptions {
         I: IntoIterator,
         I::Item: AsRef<str>,
     {
-        self.keywords = keywords.into_iter().map(util::to_lowercase).collect();
+        self.keywords = keywords.into_iter().map(|keyword| keyword.as_ref().to_string()).collect();
+        self
+    }
+
+    pub fn with_matching(mut self, matching: Matching) -> Self {
+        self.matching = matching;
+        self
+    }
+
+    pub fn with_smart_case(mut self, smart_case: bool) -> Self {
+        self.smart_case = smart_case;
         self
     }
 
@@ -172,12 +326,15 @@ impl StreamOptions {
         self.base_dir = base_dir;
         self
     }
+
+    pub fn with_proximity(mut self, proximity: Option<Proximity>) -> Self {
+        self.proximity = proximity;
+        self
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use std::path::PathBuf;
-
     use rstest::rstest;
 
     use super::*;
@@ -185,6 +342,7 @@ mod tests {
     #[rstest]
     // Case normalization
     #[case(&["fOo", "bAr"], "/foo/bar", true)]
+    #[case(&["ÄB"], "/foo/äbc", true)]
     // Last component
     #[case(&["ba"], "/foo/bar", true)]
     #[case(&["fo"], "/foo/bar", false)]
@@ -203,9 +361,218 @@ mod tests {
     #[case(&["/foo/", "/bar"], "/foo/bar", false)]
     #[case(&["/foo/", "/bar"], "/foo/baz/bar", true)]
     fn query(#[case] keywords: &[&str], #[case] path: &str, #[case] is_match: bool) {
-        let db = &mut Database::new(PathBuf::new(), Vec::new(), |_| Vec::new(), false);
+        let db = &mut Database::from_dirs(Vec::new());
         let options = StreamOptions::new(0).with_keywords(keywords.iter());
-        let stream = Stream::new(db, options);
+        let stream = Stream::new(db, options).unwrap();
         assert_eq!(is_match, stream.filter_by_keywords(path));
     }
+
+    #[rstest]
+    #[case(&["plsvc"], "/platform/services", true)]
+    #[case(&["fOo", "bAr"], "/foo/bar", true)]
+    #[case(&["fbr"], "/foo/bar", true)]
+    #[case(&["ba"], "/foo/bar", true)]
+    // Last component
+    #[case(&["fo"], "/foo/bar", false)]
+    // Order
+    #[case(&["bf"], "/foo/bar", false)]
+    fn query_fuzzy(#[case] keywords: &[&str], #[case] path: &str, #[case] is_match: bool) {
+        let db = &mut Database::from_dirs(Vec::new());
+        let options =
+            StreamOptions::new(0).with_keywords(keywords.iter()).with_matching(Matching::Fuzzy);
+        let stream = Stream::new(db, options).unwrap();
+        assert_eq!(is_match, stream.filter_by_keywords(path));
+    }
+
+    #[rstest]
+    #[case(&["docs"], "/Docs", true)]
+    #[case(&["Docs"], "/Docs", true)]
+    #[case(&["Docs"], "/docs", false)]
+    #[case(&["ÄB"], "/äbc", false)]
+    #[case(&["Foo", "bar"], "/Foo/BAR", true)]
+    #[case(&["foo", "Bar"], "/Foo/bar", false)]
+    // A literal backslash doesn't escape the next character
+    #[case(&[r"src\Main"], r"/src\Main", true)]
+    #[case(&[r"src\Main"], r"/src\main", false)]
+    fn query_smart_case(#[case] keywords: &[&str], #[case] path: &str, #[case] is_match: bool) {
+        let db = &mut Database::from_dirs(Vec::new());
+        let options = StreamOptions::new(0).with_keywords(keywords.iter()).with_smart_case(true);
+        let stream = Stream::new(db, options).unwrap();
+        assert_eq!(is_match, stream.filter_by_keywords(path));
+    }
+
+    #[rstest]
+    // Escape sequences don't make a regex case-sensitive
+    #[case(&[r"\Wfoo"], "/FOO", true)]
+    #[case(&[r"\WFoo"], "/FOO", false)]
+    fn query_smart_case_regex(
+        #[case] keywords: &[&str],
+        #[case] path: &str,
+        #[case] is_match: bool,
+    ) {
+        let db = &mut Database::from_dirs(Vec::new());
+        let options = StreamOptions::new(0)
+            .with_keywords(keywords.iter())
+            .with_matching(Matching::Regex)
+            .with_smart_case(true);
+        let stream = Stream::new(db, options).unwrap();
+        assert_eq!(is_match, stream.filter_by_keywords(path));
+    }
+
+    #[rstest]
+    #[case(&["b.r"], "/foo/bar", true)]
+    #[case(&["^/foo/bar$"], "/foo/bar", true)]
+    #[case(&["BAR"], "/foo/bar", true)]
+    #[case(&[r"\d+"], "/foo/v2", true)]
+    // Last component
+    #[case(&["f.o"], "/foo/bar", false)]
+    #[case(&["fo+/"], "/foo/bar", true)]
+    // Order
+    #[case(&["^/foo", "bar$"], "/foo/bar", true)]
+    #[case(&["bar", "foo"], "/foo/bar", false)]
+    fn query_regex(#[case] keywords: &[&str], #[case] path: &str, #[case] is_match: bool) {
+        let db = &mut Database::from_dirs(Vec::new());
+        let options =
+            StreamOptions::new(0).with_keywords(keywords.iter()).with_matching(Matching::Regex);
+        let stream = Stream::new(db, options).unwrap();
+        assert_eq!(is_match, stream.filter_by_keywords(path));
+    }
+
+    #[test]
+    fn query_regex_invalid() {
+        let db = &mut Database::from_dirs(Vec::new());
+        let options = StreamOptions::new(0).with_keywords(["("]).with_matching(Matching::Regex);
+        assert!(Stream::new(db, options).is_err());
+    }
+
+    #[test]
+    fn query_fuzzy_rank() {
+        let dir = |path: &'static str, rank| Dir {
+            path: path.into(),
+            rank,
+            last_accessed: 0,
+            pinned: false,
+        };
+        let db = &mut Database::from_dirs(vec![dir("/services", 1.5), dir("/svc", 1.0)]);
+
+        // A closer match wins over a slightly higher frecency.
+        let options = StreamOptions::new(0).with_keywords(["svc"]).with_matching(Matching::Fuzzy);
+        let mut stream = Stream::new(db, options).unwrap();
+        let mut paths = Vec::new();
+        while let Some((dir, _)) = stream.next() {
+            paths.push(dir.path.to_string());
+        }
+        assert_eq!(paths, ["/svc", "/services"]);
+    }
+
+    #[test]
+    fn query_proximity() {
+        let dir = |path: &'static str, rank| Dir {
+            path: path.into(),
+            rank,
+            last_accessed: 0,
+            pinned: false,
+        };
+        let db = &mut Database::from_dirs(vec![
+            dir("/work/other/src", 1.5),
+            dir("/work/repo/src", 1.0),
+            dir("/src", 1.2),
+        ]);
+
+        let mut query = |proximity| {
+            let options =
+                StreamOptions::new(0).with_keywords(["src"]).with_proximity(Some(proximity));
+            let mut stream = Stream::new(db, options).unwrap();
+            let mut results = Vec::new();
+            while let Some((dir, score)) = stream.next() {
+                results.push((dir.path.to_string(), score));
+            }
+            results
+        };
+
+        // Only directories inside the repository are boosted.
+        assert_eq!(
+            query(Proximity::repository("/work/repo", 1.0)),
+            [
+                ("/work/repo/src".to_string(), 8.0),
+                ("/work/other/src".to_string(), 6.0),
+                ("/src".to_string(), 4.8),
+            ]
+        );
+
+        // Outside a repository, directories are boosted by the prefix they
+        // share with the current directory.
+        assert_eq!(
+            query(Proximity::directory("/work/repo", 1.0)),
+            [
+                ("/work/other/src".to_string(), 9.0),
+                ("/work/repo/src".to_string(), 8.0),
+                ("/src".to_string(), 4.8),
+            ]
+        );
+    }
+
+    #[test]
+    fn query_explained() {
+        let dir = |path: &'static str, rank| Dir {
+            path: path.into(),
+            rank,
+            last_accessed: 0,
+            pinned: false,
+        };
+        let db = &mut Database::from_dirs(vec![dir("/foo/bar", 1.0), dir("/baz/bar", 2.0)]);
+
+        let options = StreamOptions::new(0)
+            .with_keywords(["o", "ar"])
+            .with_base_dir(Some("/baz".to_string()));
+        let mut stream = Stream::new(db, options).unwrap();
+        let explanation = stream.next_explained().unwrap();
+        assert_eq!(explanation.dir.path, "/foo/bar");
+        assert_eq!(explanation.score, 4.0);
+        assert_eq!(explanation.age_factor, 4.0);
+        assert_eq!(explanation.matches, [3..4, 6..8]);
+        assert_eq!(explanation.filtered, Some(Filtered::BaseDir));
+        assert!(stream.next_explained().is_none());
+    }
+
+    #[test]
+    fn query_learned() {
+        let dir = |path: &'static str, rank| Dir {
+            path: path.into(),
+            rank,
+            last_accessed: 0,
+            pinned: false,
+        };
+        let db = &mut Database::from_dirs(vec![dir("/api-gw", 1.0), dir("/gw", 2.0)]);
+        db.learn(&Keywords::new(&["gw"], Matching::Substring, false).unwrap(), "/api-gw", 0);
+
+        let first = |db: &mut Database, keywords: &[&str]| {
+            let options = StreamOptions::new(0).with_keywords(keywords);
+            let mut stream = Stream::new(db, options).unwrap();
+            stream.next().map(|(dir, _)| dir.path.to_string())
+        };
+        assert_eq!(first(db, &["gw"]).unwrap(), "/api-gw");
+        assert_eq!(first(db, &["g"]).unwrap(), "/gw");
+        assert_eq!(first(db, &["GW"]).unwrap(), "/api-gw");
+    }
+
+    #[test]
+    fn query_read_only() {
+        let dirs =
+            || vec![Dir { path: "/foo/bar".into(), rank: 1.0, last_accessed: 0, pinned: false }];
+
+        // Sorting and iterating should not modify the database.
+        let db = &mut Database::from_dirs(dirs());
+        let mut stream = Stream::new(db, StreamOptions::new(0)).unwrap();
+        while stream.next().is_some() {}
+        assert!(!db.dirty());
+
+        // Lazily removing an excluded directory should.
+        let db = &mut Database::from_dirs(dirs());
+        let exclude = vec![Pattern::new("/foo/*").unwrap()];
+        let mut stream = Stream::new(db, StreamOptions::new(0).with_exclude(exclude)).unwrap();
+        while stream.next().is_some() {}
+        assert!(db.dirty());
+        assert!(db.dirs().is_empty());
+    }
 }
@@ -0,0 +1,66 @@
+use glob::Pattern;
+use serde::{Deserialize, Serialize};
+
+/// A path or glob of directories that must not be added again. Stored as a
+/// plain string, and compiled once when the database is read.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(from = "String", into = "String")]
+pub struct Tombstone {
+    pub glob: String,
+    /// [`None`] if `glob` isn't a valid glob, in which case it only matches
+    /// itself.
+    pattern: Option<Pattern>,
+}
+
+impl Tombstone {
+    pub fn new(glob: String) -> Self {
+        let pattern = Pattern::new(&glob).ok();
+        Tombstone { glob, pattern }
+    }
+
+    pub fn matches(&self, path: &str) -> bool {
+        self.glob == path || self.pattern.as_ref().is_some_and(|pattern| pattern.matches(path))
+    }
+}
+
+impl From<String> for Tombstone {
+    fn from(glob: String) -> Self {
+        Tombstone::new(glob)
+    }
+}
+
+impl From<Tombstone> for String {
+    fn from(tombstone: Tombstone) -> Self {
+        tombstone.glob
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    #[rstest]
+    #[case("/foo", "/foo", true)]
+    #[case("/foo", "/foo/bar", false)]
+    #[case("/foo/*", "/foo/bar", true)]
+    #[case("/foo/[[]1]", "/foo/[1]", true)]
+    #[case("/foo/[[]1]", "/foo/1", false)]
+    #[case("/foo/[1]", "/foo/1", true)]
+    // Invalid globs only match themselves.
+    #[case("/foo/[", "/foo/[", true)]
+    #[case("/foo/[", "/foo/a", false)]
+    fn matches(#[case] glob: &str, #[case] path: &str, #[case] expected: bool) {
+        assert_eq!(Tombstone::new(glob.to_string()).matches(path), expected);
+    }
+
+    #[test]
+    fn serialize() {
+        let glob = "/foo/*".to_string();
+        let bytes = bincode::serialize(&Tombstone::new(glob.clone())).unwrap();
+        assert_eq!(bytes, bincode::serialize(&glob).unwrap());
+        let tombstone = bincode::deserialize::<Tombstone>(&bytes).unwrap();
+        assert!(tombstone.matches("/foo/bar"));
+    }
+}
@@ -1,6 +1,7 @@
 pub(crate) use crate::import::atuin::Atuin;
 pub(crate) use crate::import::autojump::Autojump;
 pub(crate) use crate::import::fasd::Fasd;
+pub(crate) use crate::import::json::Json;
 pub(crate) use crate::import::z::Z;
 pub(crate) use crate::import::z_lua::ZLua;
 pub(crate) use crate::import::zsh_z::ZshZ;
@@ -8,6 +9,7 @@ pub(crate) use crate::import::zsh_z::ZshZ;
 mod atuin;
 mod autojump;
 mod fasd;
+mod json;
 mod z;
 mod z_lua;
 mod zsh_z;
@@ -57,10 +59,12 @@ pub(crate) fn run(importer: &impl Importer, db: &mut Database) -> Result<()> {
     for entry in importer.dirs()? {
         match entry {
             Ok(dir) => {
-                if exclude_dirs.iter().any(|glob| glob.matches(&dir.path)) {
+                if exclude_dirs.iter().any(|glob| glob.matches(&dir.path))
+                    || db.is_forgotten(&dir.path)
+                {
                     continue;
                 }
-                db.add_unchecked(dir.path, dir.rank, dir.last_accessed);
+                db.add_unchecked(dir.path, dir.rank, dir.last_accessed, dir.pinned);
             }
             Err(e) => {
                 let location = match &e.path {
@@ -0,0 +1,122 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+
+use crate::db::{Dir, DirRecord};
+use crate::import::{ImportError, Importer};
+
+/// Imports a JSON array of entries, or one JSON entry per line, as written by
+/// `zoxide export`. `-` reads from stdin.
+#[derive(Clone, Debug)]
+pub(crate) struct Json {
+    pub path: PathBuf,
+}
+
+impl Importer for Json {
+    fn dirs(&self) -> Result<impl Iterator<Item = Result<Dir<'static>, ImportError>>> {
+        if self.path == Path::new("-") {
+            let mut text = String::new();
+            io::stdin().read_to_string(&mut text).context("could not read from stdin")?;
+            Ok(parse(&text, None).into_iter())
+        } else {
+            let text = fs::read_to_string(&self.path)
+                .with_context(|| format!("could not read {:?}", self.path))?;
+            Ok(parse(&text, Some(self.path.clone())).into_iter())
+        }
+    }
+}
+
+/// Parses a JSON array of entries, which is rejected as a whole if it is
+/// malformed, or one entry per line, in which case malformed lines are
+/// reported and skipped.
+fn parse(text: &str, path: Option<PathBuf>) -> Vec<Result<Dir<'static>, ImportError>> {
+    let err = |line_num, source| ImportError { path: path.clone(), line_num, source };
+
+    if text.trim_start().starts_with('[') {
+        return match serde_json::from_str::<Vec<DirRecord>>(text) {
+            Ok(records) => records.into_iter().map(|record| Ok(record.into())).collect(),
+            Err(e) => vec![Err(err(e.line(), anyhow!(e).context("invalid json")))],
+        };
+    }
+
+    text.lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| {
+            serde_json::from_str::<DirRecord>(line)
+                .map(Dir::from)
+                .map_err(|e| err(idx + 1, anyhow!(e).context("invalid entry")))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::borrow::Cow;
+
+    use super::*;
+
+    fn paths(text: &str) -> Vec<Result<String, usize>> {
+        parse(text, None)
+            .into_iter()
+            .map(|result| result.map(|dir| dir.path.into_owned()).map_err(|e| e.line_num))
+            .collect()
+    }
+
+    #[test]
+    fn array() {
+        let text = r#"[
+            {"path": "/foo", "rank": 1.0, "last_accessed": 10},
+            {"path": "/bar", "rank": 2.5, "last_accessed": 20}
+        ]"#;
+        assert_eq!(paths(text), [Ok("/foo".into()), Ok("/bar".into())]);
+        assert_eq!(paths("[]"), []);
+        assert_eq!(paths("[\n{\"path\": \"/foo\"}\n]"), [Err(2)]);
+    }
+
+    #[test]
+    fn lines() {
+        let text = "{\"path\": \"/foo\", \"rank\": 1.0, \"last_accessed\": 10}\n\
+                    {\"path\": \"/bar\", \"rank\": 2.0, \"last_accessed\": 20}\n";
+        assert_eq!(paths(text), [Ok("/foo".into()), Ok("/bar".into())]);
+        assert_eq!(paths(text.trim_end()), paths(text));
+    }
+
+    #[test]
+    fn lines_invalid() {
+        let text = "{\"path\": \"/foo\", \"rank\": 1.0, \"last_accessed\": 10}\n\
+                    \n\
+                    {\"path\": \"/bar\", \"rank\": \n\
+                    {\"path\": \"/baz\", \"rank\": 1.0}\n\
+                    {\"path\": \"/qux\", \"rank\": 1.0, \"last_accessed\": 10}\n";
+        assert_eq!(paths(text), [Ok("/foo".into()), Err(3), Err(4), Ok("/qux".into())]);
+        assert_eq!(paths(""), []);
+    }
+
+    #[test]
+    fn export_roundtrip() {
+        let dirs = [
+            Dir { path: Cow::Borrowed("/foo \"bar\""), rank: 1.5, last_accessed: 10, pinned: true },
+            Dir { path: Cow::Borrowed("/baz"), rank: 2.0, last_accessed: 20, pinned: false },
+        ];
+        let records = dirs.iter().map(DirRecord::from).collect::<Vec<_>>();
+
+        let array = serde_json::to_string_pretty(&records).unwrap();
+        let lines = records
+            .iter()
+            .map(|record| serde_json::to_string(record).unwrap() + "\n")
+            .collect::<String>();
+        for text in [array, lines] {
+            let imported = parse(&text, None).into_iter().map(Result::unwrap).collect::<Vec<_>>();
+            assert_eq!(imported.len(), dirs.len());
+            for (imported, dir) in imported.iter().zip(&dirs) {
+                assert_eq!(imported.path, dir.path);
+                assert_eq!(imported.rank, dir.rank);
+                assert_eq!(imported.last_accessed, dir.last_accessed);
+                assert_eq!(imported.pinned, dir.pinned);
+            }
+        }
+    }
+}
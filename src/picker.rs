@@ -0,0 +1,558 @@
+//! A built-in interactive picker, used in place of fzf when it isn't
+//! installed.
+
+use std::collections::BTreeSet;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::process::Command;
+
+use anyhow::{Context, Result, bail};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use crossterm::style::{Attribute, Print, SetAttribute};
+use crossterm::terminal::{self, ClearType};
+use crossterm::{cursor, execute, queue};
+
+use crate::db::{Keywords, Matching};
+use crate::error::SilentExit;
+
+/// Entries chosen in a picker, as `<score>\t<path>`, along with the Ctrl+key
+/// that accepted them, or [`None`] for Enter.
+#[derive(Debug)]
+pub struct Selection {
+    pub key: Option<char>,
+    pub entries: Vec<String>,
+}
+
+/// The picker used when no external one is available. Like fzf, entries are of
+/// the form `<score>\t<path>`, and only the path is searched.
+#[derive(Default)]
+pub struct Builtin {
+    entries: Vec<String>,
+    header: Option<String>,
+    preview: bool,
+    matching: Matching,
+    smart_case: bool,
+    /// Whether Enter selects an entry, or aborts like Esc.
+    no_accept: bool,
+    /// Arguments to `zoxide` whose output replaces the entries on startup.
+    start: Option<Vec<String>>,
+    /// Ctrl+key bindings that run `zoxide` with the given arguments, and
+    /// replace the entries with its output. `{}` is replaced by the path of
+    /// the selected entry.
+    binds: Vec<(char, Vec<String>)>,
+    /// Whether several entries can be marked with Tab.
+    multi: bool,
+    /// Ctrl+key bindings that accept the marked entries, like Enter.
+    expect: Vec<char>,
+}
+
+impl Builtin {
+    pub fn new() -> Self {
+        Builtin::default()
+    }
+
+    pub fn header(&mut self, header: impl Into<String>) -> &mut Self {
+        self.header = Some(header.into());
+        self
+    }
+
+    pub fn preview(&mut self, preview: bool) -> &mut Self {
+        self.preview = preview;
+        self
+    }
+
+    pub fn matching(&mut self, matching: Matching, smart_case: bool) -> &mut Self {
+        self.matching = matching;
+        self.smart_case = smart_case;
+        self
+    }
+
+    pub fn no_accept(&mut self) -> &mut Self {
+        self.no_accept = true;
+        self
+    }
+
+    pub fn start<I, S>(&mut self, args: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.start = Some(args.into_iter().map(Into::into).collect());
+        self
+    }
+
+    pub fn bind<I, S>(&mut self, key: char, args: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.binds.push((key, args.into_iter().map(Into::into).collect()));
+        self
+    }
+
+    pub fn multi(&mut self, expect: &[char]) -> &mut Self {
+        self.multi = true;
+        self.expect = expect.to_vec();
+        self
+    }
+
+    pub fn push(&mut self, entry: String) {
+        self.entries.push(entry);
+    }
+
+    /// Runs the picker on the terminal, and returns the selected entries.
+    pub fn run(&mut self) -> Result<Selection> {
+        if let Some(args) = self.start.clone() {
+            self.reload(&args, "")?;
+        } else if self.entries.is_empty() {
+            bail!("no match found");
+        }
+
+        let mut state = State::default();
+        state.filter(self);
+
+        let _terminal = Terminal::enter()?;
+        let stdout = &mut io::stderr();
+        loop {
+            state.draw(self, stdout).context("could not draw picker")?;
+            let Event::Key(key) = event::read().context("could not read from terminal")? else {
+                continue;
+            };
+            if key.kind == KeyEventKind::Release {
+                continue;
+            }
+            if let Some(selection) = self.handle(&mut state, key)? {
+                return Ok(selection);
+            }
+        }
+    }
+
+    /// Handles a key press, returning the selection if one was made.
+    fn handle(&mut self, state: &mut State, key: KeyEvent) -> Result<Option<Selection>> {
+        let ctrl = key.modifiers.contains(KeyModifiers::CONTROL);
+        if let KeyCode::Char(c) = key.code
+            && ctrl
+            && self.expect.contains(&c)
+        {
+            let entries = state.chosen(self);
+            return Ok((!entries.is_empty()).then_some(Selection { key: Some(c), entries }));
+        }
+        if let KeyCode::Char(c) = key.code
+            && ctrl
+            && let Some((_, args)) = self.binds.iter().find(|(bind, _)| *bind == c)
+        {
+            let path = state.selected(self).map(|entry| path_of(entry).to_string());
+            if let Some(path) = path {
+                self.reload(&args.clone(), &path)?;
+                state.marked.clear();
+                state.filter(self);
+            }
+            return Ok(None);
+        }
+
+        match key.code {
+            KeyCode::Esc => bail!(SilentExit { code: 130 }),
+            KeyCode::Char('c' | 'g' | 'q') if ctrl => bail!(SilentExit { code: 130 }),
+            KeyCode::Enter if self.no_accept => bail!(SilentExit { code: 130 }),
+            KeyCode::Enter => {
+                let entries = state.chosen(self);
+                if !entries.is_empty() {
+                    return Ok(Some(Selection { key: None, entries }));
+                }
+            }
+            KeyCode::Tab if self.multi => {
+                state.toggle();
+                state.move_by(1);
+            }
+            KeyCode::BackTab if self.multi => {
+                state.toggle();
+                state.move_by(-1);
+            }
+            KeyCode::Up | KeyCode::BackTab => state.move_by(-1),
+            KeyCode::Char('k' | 'p') if ctrl => state.move_by(-1),
+            KeyCode::Down | KeyCode::Tab => state.move_by(1),
+            KeyCode::Char('j' | 'n') if ctrl => state.move_by(1),
+            KeyCode::PageUp => state.move_by(-(state.page as isize)),
+            KeyCode::PageDown => state.move_by(state.page as isize),
+            KeyCode::Backspace => {
+                state.query.pop();
+                state.filter(self);
+            }
+            KeyCode::Char('u') if ctrl => {
+                state.query.clear();
+                state.filter(self);
+            }
+            KeyCode::Char('w') if ctrl => {
+                let query = state.query.trim_end();
+                let len = query.rfind(' ').map_or(0, |idx| idx + 1);
+                state.query.truncate(len);
+                state.filter(self);
+            }
+            KeyCode::Char(c) if !ctrl => {
+                state.query.push(c);
+                state.filter(self);
+            }
+            _ => {}
+        }
+        Ok(None)
+    }
+
+    /// Replaces the entries with the NUL-separated output of `zoxide`.
+    fn reload(&mut self, args: &[String], path: &str) -> Result<()> {
+        let exe = std::env::current_exe().context("could not find zoxide executable")?;
+        let args = args.iter().map(|arg| if arg == "{}" { path } else { arg });
+        let output = Command::new(exe).args(args).output().context("could not run zoxide")?;
+        let output = String::from_utf8(output.stdout).context("invalid unicode in output")?;
+        self.entries =
+            output.split('\0').filter(|entry| !entry.is_empty()).map(Into::into).collect();
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct State {
+    query: String,
+    /// Indices of the entries that match the query.
+    matches: Vec<usize>,
+    /// Indices of the entries that were marked with Tab.
+    marked: BTreeSet<usize>,
+    selected: usize,
+    /// Index of the first match shown in the list.
+    offset: usize,
+    /// Number of rows in the list.
+    page: usize,
+}
+
+impl State {
+    fn filter(&mut self, picker: &Builtin) {
+        let keywords = self.query.split_whitespace().collect::<Vec<_>>();
+        // Incomplete regexes are common while typing, keep the previous
+        // results until the query is valid again.
+        let Ok(keywords) = Keywords::new(&keywords, picker.matching, picker.smart_case) else {
+            return;
+        };
+        self.matches = (0..picker.entries.len())
+            .filter(|&idx| keywords.is_match(path_of(&picker.entries[idx])))
+            .collect();
+        self.selected = self.selected.min(self.matches.len().saturating_sub(1));
+    }
+
+    fn selected<'a>(&self, picker: &'a Builtin) -> Option<&'a str> {
+        self.matches.get(self.selected).map(|&idx| picker.entries[idx].as_str())
+    }
+
+    /// Returns the marked entries, or the selected one if none were marked.
+    fn chosen(&self, picker: &Builtin) -> Vec<String> {
+        if self.marked.is_empty() {
+            return self.selected(picker).map(String::from).into_iter().collect();
+        }
+        self.marked.iter().map(|&idx| picker.entries[idx].clone()).collect()
+    }
+
+    fn toggle(&mut self) {
+        if let Some(&idx) = self.matches.get(self.selected)
+            && !self.marked.remove(&idx)
+        {
+            self.marked.insert(idx);
+        }
+    }
+
+    /// Moves the selection, wrapping around at either end.
+    fn move_by(&mut self, delta: isize) {
+        let len = self.matches.len() as isize;
+        if len == 0 {
+            return;
+        }
+        let selected = self.selected as isize + delta;
+        self.selected = if delta.abs() == 1 {
+            selected.rem_euclid(len) as usize
+        } else {
+            selected.clamp(0, len - 1) as usize
+        };
+    }
+
+    fn draw(&mut self, picker: &Builtin, out: &mut impl Write) -> io::Result<()> {
+        let (width, height) = terminal::size()?;
+        let (width, height) = (width as usize, height as usize);
+
+        let header = picker.header.as_deref().map_or(Vec::new(), |header| header.lines().collect());
+        let preview_height = if picker.preview { height * 3 / 10 } else { 0 };
+        self.page = height.saturating_sub(1 + header.len() + preview_height).max(1);
+
+        // Keep the selection in view.
+        if self.selected < self.offset {
+            self.offset = self.selected;
+        } else if self.selected >= self.offset + self.page {
+            self.offset = self.selected + 1 - self.page;
+        }
+
+        queue!(out, terminal::Clear(ClearType::All))?;
+        let mut row = 1;
+        for line in &header {
+            let line = truncate(&line.replace('\t', " "), width);
+            queue!(out, cursor::MoveTo(0, row as u16), Print(line))?;
+            row += 1;
+        }
+        for (idx, &entry) in self.matches.iter().enumerate().skip(self.offset).take(self.page) {
+            let marker = if self.marked.contains(&entry) { ">" } else { " " };
+            let entry = picker.entries[entry].replace('\t', " ");
+            let line = truncate(&format!("{marker} {entry}"), width);
+            queue!(out, cursor::MoveTo(0, row as u16))?;
+            if idx == self.selected {
+                queue!(out, SetAttribute(Attribute::Reverse), Print(line))?;
+                queue!(out, SetAttribute(Attribute::Reset))?;
+            } else {
+                queue!(out, Print(line))?;
+            }
+            row += 1;
+        }
+
+        if preview_height > 0 {
+            let top = height - preview_height;
+            queue!(out, cursor::MoveTo(0, top as u16), Print("─".repeat(width)))?;
+            if let Some(entry) = self.selected(picker) {
+                let lines = list_dir(Path::new(path_of(entry)), width);
+                for (row, line) in lines.iter().take(preview_height - 1).enumerate() {
+                    queue!(out, cursor::MoveTo(0, (top + 1 + row) as u16), Print(line))?;
+                }
+            }
+        }
+
+        let mut info = format!("  {}/{}", self.matches.len(), picker.entries.len());
+        if !self.marked.is_empty() {
+            info += &format!(" ({})", self.marked.len());
+        }
+        let prompt = format!("> {}", self.query);
+        queue!(
+            out,
+            cursor::MoveTo(0, 0),
+            Print(truncate(&prompt, width)),
+            SetAttribute(Attribute::Dim),
+            Print(truncate(&info, width.saturating_sub(prompt.chars().count()))),
+            SetAttribute(Attribute::Reset),
+            cursor::MoveTo(prompt.chars().count().min(width) as u16, 0),
+        )?;
+        out.flush()
+    }
+}
+
+/// Switches the terminal to a fullscreen, raw mode for as long as this is
+/// alive.
+struct Terminal;
+
+impl Terminal {
+    fn enter() -> Result<Self> {
+        terminal::enable_raw_mode().context("could not enable raw mode")?;
+        let terminal = Terminal;
+        execute!(io::stderr(), terminal::EnterAlternateScreen)
+            .context("could not enter alternate screen")?;
+        Ok(terminal)
+    }
+}
+
+impl Drop for Terminal {
+    fn drop(&mut self) {
+        _ = execute!(io::stderr(), terminal::LeaveAlternateScreen);
+        _ = terminal::disable_raw_mode();
+    }
+}
+
+/// Returns the path of an entry, i.e. everything after the first tab.
+fn path_of(entry: &str) -> &str {
+    entry.split_once('\t').map_or(entry, |(_, path)| path)
+}
+
+/// Shortens `line` to fit in `width` columns, keeping the end of it, which is
+/// usually the most relevant part of a path.
+/// Each character is counted as one column, so wide characters can overflow.
+fn truncate(line: &str, width: usize) -> String {
+    let len = line.chars().count();
+    if len <= width {
+        return line.to_string();
+    }
+    if width == 0 {
+        return String::new();
+    }
+    let tail = line.chars().skip(len - width + 1).collect::<String>();
+    format!("…{tail}")
+}
+
+/// Lists the contents of a directory in columns, like `ls -C`.
+pub fn list_dir(path: &Path, width: usize) -> Vec<String> {
+    let mut names = match fs::read_dir(path) {
+        Ok(entries) => entries
+            .filter_map(|entry| entry.ok())
+            .map(|entry| {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                if entry.path().is_dir() { format!("{name}/") } else { name }
+            })
+            .collect::<Vec<_>>(),
+        Err(e) => return vec![truncate(&format!("could not read directory: {e}"), width)],
+    };
+    names.sort_by_key(|name| (!name.ends_with('/'), name.to_lowercase()));
+
+    let column_width = names.iter().map(|name| name.chars().count()).max().unwrap_or(0) + 2;
+    let columns = (width / column_width).max(1);
+    let rows = names.len().div_ceil(columns);
+    (0..rows)
+        .map(|row| {
+            let line = (0..columns)
+                .filter_map(|column| names.get(column * rows + row))
+                .map(|name| format!("{name:column_width$}"))
+                .collect::<String>();
+            truncate(line.trim_end(), width)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn picker(paths: &[&str]) -> Builtin {
+        let mut picker = Builtin::new();
+        for (idx, path) in paths.iter().enumerate() {
+            picker.push(format!("{:>6.1} \t{path}", idx as f64));
+        }
+        picker
+    }
+
+    #[rstest]
+    #[case("", &[0, 1, 2])]
+    #[case("ba", &[0, 1])]
+    #[case("foo", &[])]
+    #[case("foo bar", &[0])]
+    #[case("QUX", &[2])]
+    fn filter(#[case] query: &str, #[case] matches: &[usize]) {
+        let picker = picker(&["/foo/bar", "/baz", "/qux"]);
+        let mut state = State { query: query.to_string(), ..State::default() };
+        state.filter(&picker);
+        assert_eq!(state.matches, matches);
+    }
+
+    #[test]
+    fn filter_invalid_regex() {
+        let mut picker = picker(&["/foo", "/bar"]);
+        picker.matching(Matching::Regex, false);
+        let mut state = State { query: "f".to_string(), ..State::default() };
+        state.filter(&picker);
+        assert_eq!(state.matches, [0]);
+
+        // An incomplete regex keeps the previous results.
+        state.query.push('(');
+        state.filter(&picker);
+        assert_eq!(state.matches, [0]);
+    }
+
+    #[test]
+    fn filter_clamps_selection() {
+        let picker = picker(&["/foo", "/bar", "/baz"]);
+        let mut state = State::default();
+        state.filter(&picker);
+        state.selected = 2;
+        state.query = "foo".to_string();
+        state.filter(&picker);
+        assert_eq!(state.selected, 0);
+        assert_eq!(state.selected(&picker), Some(picker.entries[0].as_str()));
+    }
+
+    #[rstest]
+    // Single steps wrap around
+    #[case(0, -1, 2)]
+    #[case(2, 1, 0)]
+    #[case(1, 1, 2)]
+    // Pages stop at either end
+    #[case(1, 10, 2)]
+    #[case(1, -10, 0)]
+    fn move_by(#[case] selected: usize, #[case] delta: isize, #[case] expected: usize) {
+        let mut state = State { matches: vec![0, 1, 2], selected, ..State::default() };
+        state.move_by(delta);
+        assert_eq!(state.selected, expected);
+    }
+
+    #[test]
+    fn move_by_empty() {
+        let mut state = State::default();
+        state.move_by(1);
+        state.move_by(-10);
+        assert_eq!(state.selected, 0);
+    }
+
+    #[test]
+    fn toggle_chosen() {
+        let picker = picker(&["/foo", "/bar", "/baz"]);
+        let mut state = State::default();
+        state.filter(&picker);
+
+        // Without marks, the selected entry is chosen.
+        state.selected = 1;
+        assert_eq!(state.chosen(&picker), [picker.entries[1].as_str()]);
+
+        // Marks are kept in the order of the entries, and across filtering.
+        state.selected = 2;
+        state.toggle();
+        state.selected = 0;
+        state.toggle();
+        state.query = "bar".to_string();
+        state.filter(&picker);
+        assert_eq!(state.chosen(&picker), [picker.entries[0].as_str(), picker.entries[2].as_str()]);
+
+        // Toggling again unmarks an entry.
+        state.query.clear();
+        state.filter(&picker);
+        state.toggle();
+        assert_eq!(state.chosen(&picker), [picker.entries[2].as_str()]);
+    }
+
+    #[test]
+    fn toggle_empty() {
+        let picker = picker(&["/foo"]);
+        let mut state = State { query: "bar".to_string(), ..State::default() };
+        state.filter(&picker);
+        state.toggle();
+        assert!(state.marked.is_empty());
+        assert!(state.chosen(&picker).is_empty());
+    }
+
+    #[rstest]
+    #[case("abc", 3, "abc")]
+    #[case("abcdef", 4, "…def")]
+    #[case("abc", 0, "")]
+    #[case("", 0, "")]
+    // Widths are counted in characters, not bytes
+    #[case("/föö/bär", 8, "/föö/bär")]
+    #[case("/föö/bär", 5, "…/bär")]
+    #[case("日本語のパス", 3, "…パス")]
+    fn truncate(#[case] line: &str, #[case] width: usize, #[case] expected: &str) {
+        assert_eq!(super::truncate(line, width), expected);
+    }
+
+    #[rstest]
+    #[case("   1.0 \t/foo", "/foo")]
+    #[case("/foo", "/foo")]
+    #[case("   1.0 \t/foo\tbar", "/foo\tbar")]
+    fn path_of(#[case] entry: &str, #[case] path: &str) {
+        assert_eq!(super::path_of(entry), path);
+    }
+
+    #[test]
+    fn list_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("a")).unwrap();
+        fs::create_dir(dir.path().join("Bb")).unwrap();
+        fs::write(dir.path().join("c"), "").unwrap();
+        fs::write(dir.path().join("dd"), "").unwrap();
+
+        // Directories come first, and names are laid out in columns.
+        assert_eq!(super::list_dir(dir.path(), 10), ["a/   c", "Bb/  dd"]);
+        assert_eq!(super::list_dir(dir.path(), 100), ["a/   Bb/  c    dd"]);
+        assert_eq!(super::list_dir(dir.path(), 3), ["a/", "Bb/", "c", "dd"]);
+
+        let lines = super::list_dir(&dir.path().join("missing"), 100);
+        assert_eq!(lines.len(), 1);
+        assert!(lines[0].starts_with("could not read directory"));
+    }
+}
@@ -1,4 +1,6 @@
-use std::ffi::OsStr;
+use std::collections::HashMap;
+use std::ffi::{OsStr, OsString};
+use std::fmt::Display;
 use std::fs::{self, File, OpenOptions};
 use std::io::{self, Read, Write};
 use std::path::{Component, Path, PathBuf};
@@ -6,12 +8,12 @@ use std::process::{Child, Command, Stdio};
 use std::time::SystemTime;
 use std::{env, mem};
 
-#[cfg(windows)]
-use anyhow::anyhow;
-use anyhow::{Context, Result, bail};
+use anyhow::{Context, Result, anyhow, bail};
 
-use crate::db::{Dir, Epoch};
+use crate::config;
+use crate::db::{Alias, Dir, Epoch, Rank};
 use crate::error::SilentExit;
+use crate::picker::{Builtin, Selection};
 
 pub const SECOND: Epoch = 1;
 pub const MINUTE: Epoch = 60 * SECOND;
@@ -20,133 +22,578 @@ pub const DAY: Epoch = 24 * HOUR;
 pub const WEEK: Epoch = 7 * DAY;
 pub const MONTH: Epoch = 30 * DAY;
 
-pub struct Fzf(Command);
-
-impl Fzf {
-    const ERR_FZF_NOT_FOUND: &'static str = "could not find fzf, is it installed?";
+/// Runs an interactive picker: fzf by default, or whichever one is configured
+/// with `_ZO_PICKER`.
+pub struct Picker {
+    kind: Option<config::PickerKind>,
+    /// Args and environment for fzf. Other pickers don't understand these, so
+    /// they are configured by their [`Profile`] instead.
+    args: Vec<OsString>,
+    envs: Vec<(OsString, OsString)>,
+    preview: bool,
+    preview_command: config::Preview,
+    preview_window: Option<OsString>,
+    /// The oldest version of fzf that the caller works with, and what to call
+    /// the caller in errors.
+    min_version: Option<(FzfVersion, &'static str)>,
+    /// Ctrl+keys that accept marked entries, like Enter, if several entries
+    /// can be selected.
+    multi: Option<Vec<char>>,
+    /// Used instead of an external picker if it isn't installed, or if
+    /// configured.
+    builtin: Builtin,
+}
 
+impl Picker {
     pub fn new() -> Result<Self> {
+        Ok(Picker {
+            kind: config::picker()?,
+            args: Vec::new(),
+            envs: Vec::new(),
+            preview: false,
+            preview_command: config::preview()?,
+            preview_window: config::preview_window(),
+            min_version: None,
+            multi: None,
+            builtin: Builtin::new(),
+        })
+    }
+
+    pub fn enable_preview(&mut self) -> &mut Self {
+        self.preview = self.preview_command != config::Preview::None;
+        self.builtin.preview(self.preview);
+        self
+    }
+
+    pub fn args<I, S>(&mut self, args: I) -> &mut Self
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<OsStr>,
+    {
+        self.args.extend(args.into_iter().map(|arg| arg.as_ref().to_os_string()));
+        self
+    }
+
+    pub fn env<K, V>(&mut self, key: K, val: V) -> &mut Self
+    where
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        self.envs.push((key.as_ref().to_os_string(), val.as_ref().to_os_string()));
+        self
+    }
+
+    /// Fails to spawn fzf if it is older than `version`, rather than letting
+    /// it reject args that it doesn't understand.
+    pub fn min_version(&mut self, version: FzfVersion, name: &'static str) -> &mut Self {
+        self.min_version = Some((version, name));
+        self
+    }
+
+    /// Allows several entries to be selected, and accepted with Enter or with
+    /// Ctrl and any of `expect`. Pickers other than fzf, skim and the built-in
+    /// one only allow a single entry to be selected with Enter.
+    pub fn multi(&mut self, expect: &[char]) -> &mut Self {
+        self.multi = Some(expect.to_vec());
+        self.builtin.multi(expect);
+        self
+    }
+
+    /// Configures the built-in picker. fzf args don't apply to it, so callers
+    /// that customize fzf should set up the equivalent here.
+    pub fn builtin(&mut self, f: impl FnOnce(&mut Builtin)) -> &mut Self {
+        f(&mut self.builtin);
+        self
+    }
+
+    /// Uses the built-in picker in place of pickers other than fzf, for
+    /// interfaces that rely on fzf's key bindings.
+    pub fn fzf_or_builtin(&mut self) -> &mut Self {
+        if !matches!(self.kind, None | Some(config::PickerKind::Fzf)) {
+            self.kind = Some(config::PickerKind::Builtin);
+        }
+        self
+    }
+
+    pub fn spawn(&mut self) -> Result<PickerChild> {
+        let mut profile = match self.kind {
+            Some(config::PickerKind::Builtin) => return Ok(self.take_builtin()),
+            None | Some(config::PickerKind::Fzf) => Profile::fzf(self),
+            Some(config::PickerKind::Skim) => Profile::skim(self),
+            Some(config::PickerKind::Fzy) => Profile::fzy(),
+            Some(config::PickerKind::Peco) => Profile::peco(),
+            Some(config::PickerKind::Custom) => Profile::custom()?,
+        };
+        // Without an explicitly configured picker, fall back to the built-in
+        // one if fzf isn't installed.
+        let required = self.kind.is_some();
+        let name = profile.program;
+        let err_not_found = || anyhow!("could not find {name}, is it installed?");
+
         // On Windows, CreateProcess implicitly searches the current working
         // directory for the executable, which is a potential security issue.
         // Instead, we resolve the path to the executable and then pass it to
         // CreateProcess.
         #[cfg(windows)]
-        let program = which::which("fzf.exe").map_err(|_| anyhow!(Self::ERR_FZF_NOT_FOUND))?;
+        let program = match which::which(profile.program) {
+            Ok(program) => program,
+            Err(_) if required => return Err(err_not_found()),
+            Err(_) => return Ok(self.take_builtin()),
+        };
         #[cfg(not(windows))]
-        let program = "fzf";
+        let program = profile.program;
 
-        // TODO: check version of fzf here.
+        // Detecting the version spawns fzf, so it is skipped unless it could
+        // change anything.
+        if profile.fzf
+            && (self.min_version.is_some() || FzfVersion::gates(&profile.args))
+            && let Some(version) = FzfVersion::detect(OsStr::new(&program))
+        {
+            if let Some((min_version, caller)) = self.min_version
+                && version < min_version
+            {
+                bail!("{caller} requires fzf {min_version} or later, but found fzf {version}");
+            }
+            profile.args = version.negotiate(mem::take(&mut profile.args));
+        }
 
         let mut cmd = Command::new(program);
-        cmd.args([
+        cmd.args(&profile.args).stdin(Stdio::piped()).stdout(Stdio::piped());
+        if self.preview && profile.preview {
+            cmd.envs([
+                // Enables colorized `ls` output on macOS / FreeBSD.
+                ("CLICOLOR", "1"),
+                // Forces colorized `ls` output when the output is not a
+                // TTY (like in fzf's preview window) on macOS /
+                // FreeBSD.
+                ("CLICOLOR_FORCE", "1"),
+                // Ensures that the preview command is run in a
+                // POSIX-compliant shell, regardless of what shell the
+                // user has selected.
+                ("SHELL", "sh"),
+            ]);
+        }
+        if profile.fzf {
+            cmd.envs(self.envs.iter().map(|(key, val)| (key, val)));
+        }
+
+        match cmd.spawn() {
+            Ok(child) => Ok(PickerChild::External(External {
+                child,
+                profile,
+                entries: HashMap::new(),
+                written: 0,
+            })),
+            Err(e) if e.kind() == io::ErrorKind::NotFound && !required => Ok(self.take_builtin()),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Err(err_not_found()),
+            Err(e) => Err(e).with_context(|| format!("could not launch {}", profile.program)),
+        }
+    }
+
+    fn take_builtin(&mut self) -> PickerChild {
+        // The built-in picker always shows its own listing, unless previews
+        // are disabled.
+        if self.preview_command == config::Preview::None {
+            self.builtin.preview(false);
+        }
+        PickerChild::Builtin(mem::take(&mut self.builtin))
+    }
+}
+
+/// A version of fzf, used to leave out args that older versions don't
+/// support.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub struct FzfVersion(pub u32, pub u32, pub u32);
+
+impl FzfVersion {
+    /// Args that were added in later versions of fzf, along with the version
+    /// that added them, and what to replace them with on older versions.
+    const ARGS: &[(&str, FzfVersion, Option<&str>)] = &[
+        ("--border-label=", FzfVersion(0, 35, 0), None),
+        ("--border=sharp", FzfVersion(0, 23, 0), Some("--border")),
+        ("--color=label:", FzfVersion(0, 35, 0), None),
+        ("--info=inline", FzfVersion(0, 19, 0), Some("--inline-info")),
+        ("--padding=", FzfVersion(0, 22, 0), None),
+        (
+            "--preview-window=down,30%,sharp",
+            FzfVersion(0, 25, 0),
+            Some("--preview-window=down:30%"),
+        ),
+    ];
+
+    /// Runs `fzf --version`. Returns [`None`] if fzf can't be run, or if its
+    /// version can't be parsed, in which case all args are assumed to be
+    /// supported.
+    fn detect(program: &OsStr) -> Option<Self> {
+        let output = Command::new(program).arg("--version").stdin(Stdio::null()).output().ok()?;
+        if !output.status.success() {
+            return None;
+        }
+        Self::parse(str::from_utf8(&output.stdout).ok()?)
+    }
+
+    /// Parses the output of `fzf --version`, e.g. `0.44.1 (d7d2ac3)`.
+    fn parse(output: &str) -> Option<Self> {
+        let version = output.split_whitespace().next()?;
+        let mut parts = version.split(['.', '-']).map(|part| part.parse::<u32>());
+        let major = parts.next()?.ok()?;
+        let minor = parts.next()?.ok()?;
+        let patch = parts.next().and_then(Result::ok).unwrap_or(0);
+        Some(FzfVersion(major, minor, patch))
+    }
+
+    /// Whether any of `args` depend on the version of fzf.
+    fn gates(args: &[OsString]) -> bool {
+        args.iter().any(|arg| Self::find(arg).is_some())
+    }
+
+    fn find(arg: &OsStr) -> Option<&'static (&'static str, FzfVersion, Option<&'static str>)> {
+        let arg = arg.to_str()?;
+        Self::ARGS.iter().find(|(prefix, ..)| arg.starts_with(prefix))
+    }
+
+    /// Removes or replaces args that this version doesn't support.
+    fn negotiate(self, args: Vec<OsString>) -> Vec<OsString> {
+        args.into_iter()
+            .filter_map(|arg| match Self::find(&arg) {
+                Some(&(_, version, replacement)) if self < version => {
+                    replacement.map(OsString::from)
+                }
+                _ => Some(arg),
+            })
+            .collect()
+    }
+}
+
+impl Display for FzfVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}.{}.{}", self.0, self.1, self.2)
+    }
+}
+
+/// How to run an external picker, and how entries are passed to it.
+struct Profile {
+    program: &'static str,
+    args: Vec<OsString>,
+    framing: config::Framing,
+    /// Whether the picker is fzf, so that fzf-specific settings apply.
+    fzf: bool,
+    /// Whether the picker uses fzf's exit codes.
+    fzf_exit_codes: bool,
+    /// Whether the picker shows previews, which need colors to be forced.
+    preview: bool,
+    /// Whether the output starts with the key that accepted the selection.
+    expect: bool,
+}
+
+impl Profile {
+    /// Previews the path in the second column of an entry, for fzf and skim.
+    fn preview_args(picker: &Picker, default_window: &str) -> Vec<OsString> {
+        let Some(command) = Self::preview_command(&picker.preview_command) else {
+            return Vec::new();
+        };
+        let mut window = OsString::from("--preview-window=");
+        window.push(picker.preview_window.as_deref().unwrap_or(default_window.as_ref()));
+        vec![format!("--preview={command}").into(), window]
+    }
+
+    /// Returns the preview command for fzf and skim, where `{2..}` is the path.
+    fn preview_command(preview: &config::Preview) -> Option<String> {
+        // Non-POSIX args are only available on certain operating systems.
+        let ls = if cfg!(target_os = "linux") {
+            r"\command -p ls -Cp --color=always --group-directories-first {2..}"
+        } else {
+            r"\command -p ls -Cp {2..}"
+        };
+        let command = match preview {
+            config::Preview::Ls => ls.to_string(),
+            config::Preview::Eza => {
+                r"\command eza --grid --color=always --group-directories-first {2..}".to_string()
+            }
+            config::Preview::Lsd => {
+                r"\command lsd --color=always --group-directories-first {2..}".to_string()
+            }
+            config::Preview::Tree => r"\command tree -C -L 2 {2..}".to_string(),
+            config::Preview::Git => format!(
+                r"\command git -C {{2..}} -c color.status=always status --short --branch 2>/dev/null && echo; {ls}"
+            ),
+            config::Preview::Zoxide => r"\command zoxide preview {2..}".to_string(),
+            config::Preview::None => return None,
+            config::Preview::Command(command) => command.replace("{}", "{2..}"),
+        };
+        Some(command)
+    }
+
+    /// Args for selecting several entries with fzf and skim.
+    fn multi_args(expect: &[char]) -> Vec<OsString> {
+        let expect = expect.iter().map(|key| format!("ctrl-{key}")).collect::<Vec<_>>();
+        let mut args = vec!["--multi".into(), "--print0".into()];
+        if !expect.is_empty() {
+            args.push(format!("--expect={}", expect.join(",")).into());
+        }
+        args
+    }
+
+    fn fzf(picker: &Picker) -> Self {
+        let mut args = [
             // Search mode
             "--delimiter=\t",
             "--nth=2",
             // Scripting
             "--read0",
-        ])
-        .stdin(Stdio::piped())
-        .stdout(Stdio::piped());
+        ]
+        .map(OsString::from)
+        .to_vec();
+        args.extend(picker.args.iter().cloned());
 
-        Ok(Fzf(cmd))
+        // Previews are only supported on UNIX.
+        let preview = picker.preview && cfg!(unix);
+        if preview {
+            // Rounded edges don't display correctly on some terminals.
+            args.extend(Self::preview_args(picker, "down,30%,sharp"));
+        }
+
+        let multi = picker.multi.is_some();
+        let expect = picker.multi.as_ref().is_some_and(|keys| !keys.is_empty());
+        if let Some(expect) = &picker.multi {
+            args.extend(Self::multi_args(expect));
+            // Callers bind Tab to moving down, it should mark entries instead.
+            // These actions predate chaining them with `+`, so they don't need
+            // to be negotiated.
+            args.push("--bind=tab:toggle-down,btab:toggle-up".into());
+        }
+
+        let framing = config::Framing { read0: true, print0: multi, scores: true };
+        Profile { program: "fzf", args, framing, fzf: true, fzf_exit_codes: true, preview, expect }
     }
 
-    pub fn enable_preview(&mut self) -> &mut Self {
-        // Previews are only supported on UNIX.
-        if !cfg!(unix) {
-            return self;
+    /// skim accepts most of fzf's args, but not all of them, so it gets a
+    /// fixed set of its own.
+    fn skim(picker: &Picker) -> Self {
+        let args = [
+            // Search mode
+            "--delimiter=\t",
+            "--nth=2",
+            "--exact",
+            // Search result
+            "--no-sort",
+            // Interface
+            "--cycle",
+            "--keep-right",
+            // Layout
+            "--height=45%",
+            "--inline-info",
+            "--layout=reverse",
+            // Display
+            "--tabstop=1",
+            // Scripting
+            "--exit-0",
+            "--read0",
+        ];
+        let mut args = args.into_iter().map(OsString::from).collect::<Vec<_>>();
+        let preview = picker.preview && cfg!(unix);
+        if preview {
+            args.extend(Self::preview_args(picker, "down:30%"));
         }
 
-        self.args([
-            // Non-POSIX args are only available on certain operating systems.
-            if cfg!(target_os = "linux") {
-                r"--preview=\command -p ls -Cp --color=always --group-directories-first {2..}"
-            } else {
-                r"--preview=\command -p ls -Cp {2..}"
-            },
-            // Rounded edges don't display correctly on some terminals.
-            "--preview-window=down,30%,sharp",
-        ])
-        .envs([
-            // Enables colorized `ls` output on macOS / FreeBSD.
-            ("CLICOLOR", "1"),
-            // Forces colorized `ls` output when the output is not a
-            // TTY (like in fzf's preview window) on macOS /
-            // FreeBSD.
-            ("CLICOLOR_FORCE", "1"),
-            // Ensures that the preview command is run in a
-            // POSIX-compliant shell, regardless of what shell the
-            // user has selected.
-            ("SHELL", "sh"),
-        ])
+        let multi = picker.multi.is_some();
+        let expect = picker.multi.as_ref().is_some_and(|keys| !keys.is_empty());
+        if let Some(expect) = &picker.multi {
+            args.extend(Self::multi_args(expect));
+        }
+
+        let framing = config::Framing { read0: true, print0: multi, scores: true };
+        Profile { program: "sk", args, framing, fzf: false, fzf_exit_codes: true, preview, expect }
     }
 
-    pub fn args<I, S>(&mut self, args: I) -> &mut Self
-    where
-        I: IntoIterator<Item = S>,
-        S: AsRef<OsStr>,
-    {
-        self.0.args(args);
-        self
+    /// fzy can't restrict the search to the path column, so it is only given
+    /// paths.
+    fn fzy() -> Self {
+        Profile {
+            program: "fzy",
+            args: vec!["--lines=20".into()],
+            framing: config::Framing::default(),
+            fzf: false,
+            fzf_exit_codes: false,
+            preview: false,
+            expect: false,
+        }
     }
 
-    pub fn env<K, V>(&mut self, key: K, val: V) -> &mut Self
-    where
-        K: AsRef<OsStr>,
-        V: AsRef<OsStr>,
-    {
-        self.0.env(key, val);
-        self
+    /// Like fzy, peco is only given paths.
+    fn peco() -> Self {
+        Profile {
+            program: "peco",
+            args: Vec::new(),
+            framing: config::Framing::default(),
+            fzf: false,
+            fzf_exit_codes: false,
+            preview: false,
+            expect: false,
+        }
     }
 
-    pub fn envs<I, K, V>(&mut self, vars: I) -> &mut Self
-    where
-        I: IntoIterator<Item = (K, V)>,
-        K: AsRef<OsStr>,
-        V: AsRef<OsStr>,
-    {
-        self.0.envs(vars);
-        self
+    /// Runs `_ZO_PICKER_COMMAND` in a shell, framed according to
+    /// `_ZO_PICKER_FRAMING`.
+    fn custom() -> Result<Self> {
+        let command = config::picker_command()
+            .context("_ZO_PICKER_COMMAND must be set when _ZO_PICKER is custom")?;
+        let (program, flag) = if cfg!(windows) { ("cmd", "/C") } else { ("sh", "-c") };
+        Ok(Profile {
+            program,
+            args: vec![flag.into(), command],
+            framing: config::picker_framing()?,
+            fzf: false,
+            fzf_exit_codes: false,
+            preview: false,
+            expect: false,
+        })
     }
 
-    pub fn spawn(&mut self) -> Result<FzfChild> {
-        match self.0.spawn() {
-            Ok(child) => Ok(FzfChild(child)),
-            Err(e) if e.kind() == io::ErrorKind::NotFound => bail!(Self::ERR_FZF_NOT_FOUND),
-            Err(e) => Err(e).context("could not launch fzf"),
+    /// Parses the output of the picker into a selection. Entries are returned
+    /// as `<score>\t<path>`, using `entries` to look up the ones that were
+    /// written as paths alone.
+    fn selection(&self, output: &str, entries: &HashMap<String, String>) -> Result<Selection> {
+        let framing = &self.framing;
+        let separator = if framing.print0 { '\0' } else { '\n' };
+        let mut lines = if framing.print0 {
+            output.split_terminator(separator).collect::<Vec<_>>()
+        } else {
+            // A single entry is returned, which may contain newlines.
+            vec![output.strip_suffix(separator).unwrap_or(output)]
         }
+        .into_iter();
+
+        // The key is empty when the selection is accepted with Enter.
+        let key = if self.expect {
+            lines
+                .next()
+                .and_then(|key| key.strip_prefix("ctrl-"))
+                .and_then(|key| key.chars().next())
+        } else {
+            None
+        };
+        let entries = lines
+            .filter(|line| !line.is_empty())
+            .map(|line| match entries.get(line) {
+                _ if framing.scores => line.to_string(),
+                Some(entry) => entry.clone(),
+                None => format!("\t{line}"),
+            })
+            .collect::<Vec<_>>();
+        if entries.is_empty() {
+            bail!("no match found");
+        }
+        Ok(Selection { key, entries })
     }
 }
 
-pub struct FzfChild(Child);
+pub enum PickerChild {
+    External(External),
+    Builtin(Builtin),
+}
+
+pub struct External {
+    child: Child,
+    profile: Profile,
+    /// Entries that were written as paths alone, by path, so that the full
+    /// entry can be returned when one is selected.
+    entries: HashMap<String, String>,
+    written: usize,
+}
+
+impl PickerChild {
+    pub fn write(&mut self, dir: &Dir, score: Rank) -> Result<Option<Selection>> {
+        self.write_entry(dir.display().with_score(score).with_separator('\t'))
+    }
+
+    /// Writes an alias, marked by its name in place of the score.
+    pub fn write_alias(&mut self, alias: &Alias) -> Result<Option<Selection>> {
+        self.write_entry(format_args!("{:>7}\t{}", format!("@{}", alias.name), alias.path))
+    }
 
-impl FzfChild {
-    pub fn write(&mut self, dir: &Dir, now: Epoch) -> Result<Option<String>> {
-        let handle = self.0.stdin.as_mut().unwrap();
-        match write!(handle, "{}\0", dir.display().with_score(now).with_separator('\t')) {
-            Ok(()) => Ok(None),
+    fn write_entry(&mut self, entry: impl Display) -> Result<Option<Selection>> {
+        let external = match self {
+            PickerChild::External(external) => external,
+            PickerChild::Builtin(picker) => {
+                picker.push(entry.to_string());
+                return Ok(None);
+            }
+        };
+
+        let entry = entry.to_string();
+        let framing = &external.profile.framing;
+        let text = if framing.scores {
+            entry.as_str()
+        } else {
+            entry.split_once('\t').map_or(entry.as_str(), |(_, path)| path)
+        };
+        // Newlines can't be represented when entries are separated by them.
+        if !framing.read0 && text.contains('\n') {
+            return Ok(None);
+        }
+
+        let handle = external.child.stdin.as_mut().unwrap();
+        let separator = if framing.read0 { '\0' } else { '\n' };
+        match write!(handle, "{text}{separator}") {
+            Ok(()) => {
+                if !framing.scores {
+                    external.entries.insert(text.to_string(), entry.clone());
+                }
+                external.written += 1;
+                Ok(None)
+            }
             Err(e) if e.kind() == io::ErrorKind::BrokenPipe => self.wait().map(Some),
-            Err(e) => Err(e).context("could not write to fzf"),
+            Err(e) => Err(e).with_context(|| format!("could not write to {}", external.program())),
         }
     }
 
-    pub fn wait(&mut self) -> Result<String> {
+    /// Waits for a selection. Entries are returned as `<score>\t<path>`, even
+    /// from pickers that are only given paths.
+    pub fn wait(&mut self) -> Result<Selection> {
+        let external = match self {
+            PickerChild::External(external) => external,
+            PickerChild::Builtin(picker) => return picker.run(),
+        };
+        let program = external.program();
+        let child = &mut external.child;
+
         // Drop stdin to prevent deadlock.
-        mem::drop(self.0.stdin.take());
+        mem::drop(child.stdin.take());
 
-        let mut stdout = self.0.stdout.take().unwrap();
+        let mut stdout = child.stdout.take().unwrap();
         let mut output = String::new();
-        stdout.read_to_string(&mut output).context("failed to read from fzf")?;
-
-        let status = self.0.wait().context("wait failed on fzf")?;
-        match status.code() {
-            Some(0) => Ok(output),
-            Some(1) => bail!("no match found"),
-            Some(2) => bail!("fzf returned an error"),
-            Some(130) => bail!(SilentExit { code: 130 }),
-            Some(128..=254) | None => bail!("fzf was terminated"),
-            _ => bail!("fzf returned an unknown error"),
+        stdout
+            .read_to_string(&mut output)
+            .with_context(|| format!("failed to read from {program}"))?;
+
+        let status = child.wait().with_context(|| format!("wait failed on {program}"))?;
+        if external.profile.fzf_exit_codes {
+            match status.code() {
+                Some(0) => {}
+                Some(1) => bail!("no match found"),
+                Some(2) => bail!("{program} returned an error"),
+                Some(130) => bail!(SilentExit { code: 130 }),
+                Some(128..=254) | None => bail!("{program} was terminated"),
+                _ => bail!("{program} returned an unknown error"),
+            }
+        } else if external.written == 0 {
+            bail!("no match found");
+        } else if !status.success() {
+            // Most pickers exit with an error when cancelled.
+            // Exit codes that don't fit in a byte are reported as 1, rather
+            // than being truncated, possibly to 0.
+            let code = status.code().and_then(|code| u8::try_from(code).ok());
+            bail!(SilentExit { code: code.filter(|&code| code != 0).unwrap_or(1) });
         }
+
+        external.profile.selection(&output, &external.entries)
+    }
+}
+
+impl External {
+    fn program(&self) -> &'static str {
+        self.profile.program
     }
 }
 
@@ -250,6 +697,31 @@ pub fn current_dir() -> Result<PathBuf> {
     env::current_dir().context("could not get current directory")
 }
 
+/// Returns the current directory as the shell sees it, which may go through
+/// symlinks. `$PWD` is only trusted if it still refers to the current
+/// directory.
+pub fn logical_current_dir() -> Result<PathBuf> {
+    let current_dir = current_dir()?;
+    if let Some(pwd) = env::var_os("PWD").map(PathBuf::from)
+        && pwd.is_absolute()
+        && fs::canonicalize(&pwd).ok() == fs::canonicalize(&current_dir).ok()
+    {
+        return Ok(pwd);
+    }
+    Ok(current_dir)
+}
+
+/// Formats a duration in seconds in a compact form, e.g. `3h`.
+pub fn format_duration(secs: Epoch) -> String {
+    match secs {
+        _ if secs < MINUTE => format!("{secs}s"),
+        _ if secs < HOUR => format!("{}m", secs / MINUTE),
+        _ if secs < DAY => format!("{}h", secs / HOUR),
+        _ if secs < WEEK => format!("{}d", secs / DAY),
+        _ => format!("{}w", secs / WEEK),
+    }
+}
+
 pub fn current_time() -> Result<Epoch> {
     let current_time = SystemTime::now()
         .duration_since(SystemTime::UNIX_EPOCH)
@@ -264,6 +736,27 @@ pub fn path_to_str(path: &impl AsRef<Path>) -> Result<&str> {
     path.to_str().with_context(|| format!("invalid unicode in path: {}", path.display()))
 }
 
+/// Reads NUL-separated paths from stdin. Unlike lines, these can represent
+/// paths that contain newlines.
+pub fn read_stdin0() -> Result<Vec<String>> {
+    let mut bytes = Vec::new();
+    io::stdin().read_to_end(&mut bytes).context("could not read from stdin")?;
+    split0(&bytes)
+}
+
+/// Splits NUL-separated paths, skipping empty ones.
+fn split0(bytes: &[u8]) -> Result<Vec<String>> {
+    bytes
+        .split(|&byte| byte == b'\0')
+        .filter(|path| !path.is_empty())
+        .map(|path| {
+            String::from_utf8(path.to_vec()).with_context(|| {
+                format!("invalid unicode in path: {}", String::from_utf8_lossy(path))
+            })
+        })
+        .collect()
+}
+
 /// Returns the absolute version of a path. Like
 /// [`std::path::Path::canonicalize`], but doesn't resolve symlinks.
 pub fn resolve_path(path: impl AsRef<Path>) -> Result<PathBuf> {
@@ -378,3 +871,159 @@ pub fn to_lowercase(s: impl AsRef<str>) -> String {
     let s = s.as_ref();
     if s.is_ascii() { s.to_ascii_lowercase() } else { s.to_lowercase() }
 }
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+
+    use super::*;
+
+    fn profile(framing: config::Framing, expect: bool) -> Profile {
+        Profile {
+            program: "picker",
+            args: Vec::new(),
+            framing,
+            fzf: false,
+            fzf_exit_codes: false,
+            preview: false,
+            expect,
+        }
+    }
+
+    #[rstest]
+    #[case("0.44.1 (d7d2ac3)", Some(FzfVersion(0, 44, 1)))]
+    #[case("0.17.5 (brew)", Some(FzfVersion(0, 17, 5)))]
+    #[case("0.20.0-devel", Some(FzfVersion(0, 20, 0)))]
+    #[case("0.9", Some(FzfVersion(0, 9, 0)))]
+    #[case("", None)]
+    #[case("fzf", None)]
+    #[case("1.x.0", None)]
+    fn fzf_version_parse(#[case] output: &str, #[case] expected: Option<FzfVersion>) {
+        assert_eq!(FzfVersion::parse(output), expected);
+    }
+
+    #[rstest]
+    #[case(FzfVersion(0, 44, 1), &["--nth=2", "--border=sharp", "--info=inline", "--padding=1"])]
+    #[case(FzfVersion(0, 22, 0), &["--nth=2", "--border", "--info=inline", "--padding=1"])]
+    #[case(FzfVersion(0, 18, 0), &["--nth=2", "--border", "--inline-info"])]
+    fn fzf_version_negotiate(#[case] version: FzfVersion, #[case] expected: &[&str]) {
+        let args = ["--nth=2", "--border=sharp", "--info=inline", "--padding=1"];
+        let args = version.negotiate(args.map(OsString::from).to_vec());
+        assert_eq!(args, expected);
+    }
+
+    #[rstest]
+    #[case(&[], false)]
+    #[case(&["--nth=2", "--bind=tab:toggle-down,btab:toggle-up"], false)]
+    #[case(&["--nth=2", "--info=inline"], true)]
+    fn fzf_version_gates(#[case] args: &[&str], #[case] expected: bool) {
+        let args = args.iter().map(OsString::from).collect::<Vec<_>>();
+        assert_eq!(FzfVersion::gates(&args), expected);
+    }
+
+    #[rstest]
+    #[case(config::Preview::Tree, Some(r"\command tree -C -L 2 {2..}"))]
+    #[case(config::Preview::Zoxide, Some(r"\command zoxide preview {2..}"))]
+    #[case(config::Preview::None, None)]
+    #[case(config::Preview::Command("bat {}/README.md".to_string()), Some("bat {2..}/README.md"))]
+    #[case(config::Preview::Command("diff {} {}".to_string()), Some("diff {2..} {2..}"))]
+    fn preview_command(#[case] preview: config::Preview, #[case] expected: Option<&str>) {
+        assert_eq!(Profile::preview_command(&preview).as_deref(), expected);
+    }
+
+    #[rstest]
+    #[case(b"", &[])]
+    #[case(b"\0\0", &[])]
+    #[case(b"/foo", &["/foo"])]
+    #[case(b"/foo\0/bar\0", &["/foo", "/bar"])]
+    #[case(b"/foo\0\0/bar", &["/foo", "/bar"])]
+    #[case(b"/foo\nbar\0/b\xc3\xa4r\0", &["/foo\nbar", "/b\u{e4}r"])]
+    fn split0(#[case] bytes: &[u8], #[case] expected: &[&str]) {
+        assert_eq!(super::split0(bytes).unwrap(), expected);
+    }
+
+    #[test]
+    fn split0_invalid_unicode() {
+        assert!(super::split0(b"/foo\0/b\xffr\0").is_err());
+    }
+
+    #[rstest]
+    // Entries written as paths alone are mapped back to the full entry.
+    #[case("/a\n", &["   1.0\t/a"])]
+    #[case("/a", &["   1.0\t/a"])]
+    // Entries that weren't written are returned without a score.
+    #[case("/c\n", &["\t/c"])]
+    // A single entry is returned, even if it contains newlines.
+    #[case("/b\nc\n", &["\t/b\nc"])]
+    fn selection_paths(#[case] output: &str, #[case] expected: &[&str]) {
+        let entries = HashMap::from([("/a".to_string(), "   1.0\t/a".to_string())]);
+        let selection =
+            profile(config::Framing::default(), false).selection(output, &entries).unwrap();
+        assert_eq!(selection.key, None);
+        assert_eq!(selection.entries, expected);
+    }
+
+    #[test]
+    fn selection_print0() {
+        let framing = config::Framing { read0: true, print0: true, scores: false };
+        let entries = HashMap::from([("/a".to_string(), "   1.0\t/a".to_string())]);
+        let selection = profile(framing, false).selection("/a\0/b\nc\0", &entries).unwrap();
+        assert_eq!(selection.entries, ["   1.0\t/a", "\t/b\nc"]);
+    }
+
+    #[test]
+    fn selection_scores() {
+        let framing = config::Framing { read0: true, print0: false, scores: true };
+        let selection = profile(framing, false).selection("   1.0\t/a\n", &HashMap::new()).unwrap();
+        assert_eq!(selection.entries, ["   1.0\t/a"]);
+    }
+
+    #[rstest]
+    // Accepted with a key bound by `--expect`
+    #[case("ctrl-d\0   1.0\t/a\0   2.0\t/b\0", Some('d'), &["   1.0\t/a", "   2.0\t/b"])]
+    // Accepted with Enter
+    #[case("\0   1.0\t/a\0", None, &["   1.0\t/a"])]
+    // Paths may contain newlines when entries are NUL-terminated.
+    #[case("ctrl-o\0   1.0\t/a\nb\0", Some('o'), &["   1.0\t/a\nb"])]
+    fn selection_expect(
+        #[case] output: &str,
+        #[case] key: Option<char>,
+        #[case] expected: &[&str],
+    ) {
+        // Framing used by fzf and skim with `--multi`.
+        let framing = config::Framing { read0: true, print0: true, scores: true };
+        let selection = profile(framing, true).selection(output, &HashMap::new()).unwrap();
+        assert_eq!(selection.key, key);
+        assert_eq!(selection.entries, expected);
+    }
+
+    #[test]
+    fn selection_expect_empty() {
+        let framing = config::Framing { read0: true, print0: true, scores: true };
+        assert!(profile(framing, true).selection("ctrl-d\0", &HashMap::new()).is_err());
+    }
+
+    #[rstest]
+    #[case("")]
+    #[case("\n")]
+    fn selection_empty(#[case] output: &str) {
+        let selection =
+            profile(config::Framing::default(), false).selection(output, &HashMap::new());
+        assert!(selection.is_err());
+    }
+
+    #[rstest]
+    #[case(0, "0s")]
+    #[case(MINUTE - 1, "59s")]
+    #[case(MINUTE, "1m")]
+    #[case(HOUR - 1, "59m")]
+    #[case(HOUR, "1h")]
+    #[case(DAY - 1, "23h")]
+    #[case(DAY, "1d")]
+    #[case(WEEK - 1, "6d")]
+    #[case(WEEK, "1w")]
+    #[case(10 * WEEK, "10w")]
+    fn duration(#[case] secs: Epoch, #[case] expected: &str) {
+        assert_eq!(format_duration(secs), expected);
+    }
+}
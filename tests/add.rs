@@ -0,0 +1,63 @@
+//! Tests for `zoxide add --stdin0`.
+
+use std::fs;
+use std::path::Path;
+
+use assert_cmd::Command;
+
+fn zoxide(data_dir: &Path) -> Command {
+    let mut cmd = Command::cargo_bin("zoxide").unwrap();
+    cmd.env("_ZO_DATA_DIR", data_dir)
+        .env("_ZO_EXCLUDE_DIRS", "")
+        .env_remove("_ZO_RESOLVE_SYMLINKS");
+    cmd
+}
+
+/// Returns the paths in the database.
+fn list(data_dir: &Path) -> Vec<String> {
+    let output = zoxide(data_dir).args(["query", "--list", "--all", "--print0"]).output().unwrap();
+    let mut paths = String::from_utf8(output.stdout)
+        .unwrap()
+        .split_terminator('\0')
+        .map(String::from)
+        .collect::<Vec<_>>();
+    paths.sort();
+    paths
+}
+
+#[test]
+#[cfg(unix)]
+fn add_stdin0() {
+    let data_dir = tempfile::tempdir().unwrap();
+    let dirs = tempfile::tempdir().unwrap();
+    let [plain, newline, argv] = ["plain", "new\nline", "arg\nv"].map(|name| {
+        let path = dirs.path().join(name);
+        fs::create_dir(&path).unwrap();
+        path.to_str().unwrap().to_string()
+    });
+
+    // Paths from stdin may contain newlines, paths from arguments may not.
+    zoxide(data_dir.path())
+        .args(["add", "--stdin0", &argv])
+        .write_stdin(format!("{plain}\0{newline}\0\0"))
+        .assert()
+        .success();
+    let mut expected = vec![plain.clone(), newline.clone()];
+    expected.sort();
+    assert_eq!(list(data_dir.path()), expected);
+
+    // Arguments are added alongside stdin.
+    zoxide(data_dir.path())
+        .args(["add", "--stdin0", dirs.path().to_str().unwrap()])
+        .write_stdin("")
+        .assert()
+        .success();
+    assert_eq!(list(data_dir.path()).len(), 3);
+
+    // Missing directories fail, as with arguments.
+    zoxide(data_dir.path())
+        .args(["add", "--stdin0"])
+        .write_stdin(format!("{plain}\0{}/missing\0", dirs.path().display()))
+        .assert()
+        .failure();
+}
@@ -0,0 +1,49 @@
+//! Tests for `zoxide mv`.
+
+use std::fs;
+use std::path::Path;
+
+use assert_cmd::Command;
+
+fn zoxide(data_dir: &Path) -> Command {
+    let mut cmd = Command::cargo_bin("zoxide").unwrap();
+    cmd.env("_ZO_DATA_DIR", data_dir).env("_ZO_EXCLUDE_DIRS", "").env("_ZO_RESOLVE_SYMLINKS", "1");
+    cmd
+}
+
+#[test]
+#[cfg(unix)]
+fn mv_resolve_symlinks() {
+    let data_dir = tempfile::tempdir().unwrap();
+    let dirs = tempfile::tempdir().unwrap();
+    let real = fs::canonicalize(dirs.path()).unwrap().join("real");
+    let link = dirs.path().join("link");
+    fs::create_dir_all(real.join("old/sub")).unwrap();
+    std::os::unix::fs::symlink(&real, &link).unwrap();
+
+    // Paths are added through the link, and stored resolved.
+    zoxide(data_dir.path()).arg("add").arg(link.join("old/sub")).assert().success();
+    zoxide(data_dir.path())
+        .args(["remove", "--forget"])
+        .arg(real.join("old/*.tmp"))
+        .assert()
+        .success();
+
+    // The old path no longer exists, but is resolved through its parent.
+    fs::rename(real.join("old"), real.join("new")).unwrap();
+    zoxide(data_dir.path())
+        .arg("mv")
+        .arg(link.join("old"))
+        .arg(link.join("new"))
+        .assert()
+        .success();
+
+    let output = zoxide(data_dir.path()).args(["query", "--list", "--all"]).output().unwrap();
+    let expected = format!("{}\n", real.join("new/sub").display());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
+
+    // Forgotten globs under the old path are moved too.
+    let output = zoxide(data_dir.path()).args(["remove", "--forgotten"]).output().unwrap();
+    let expected = format!("{}\n", real.join("new/*.tmp").display());
+    assert_eq!(String::from_utf8(output.stdout).unwrap(), expected);
+}
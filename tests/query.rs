@@ -0,0 +1,269 @@
+//! Tests for `zoxide query` that need a database and real directories.
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use std::{env, fs, iter};
+
+use assert_cmd::Command;
+
+fn zoxide(data_dir: &Path) -> Command {
+    let mut cmd = Command::cargo_bin("zoxide").unwrap();
+    cmd.env("_ZO_DATA_DIR", data_dir).env("_ZO_EXCLUDE_DIRS", "").env_remove("_ZO_PROXIMITY");
+    cmd
+}
+
+/// Returns the contents of every file in `dir`.
+fn snapshot(dir: &Path) -> BTreeMap<String, Vec<u8>> {
+    fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| {
+            let entry = entry.unwrap();
+            (entry.file_name().into_string().unwrap(), fs::read(entry.path()).unwrap())
+        })
+        .collect()
+}
+
+/// Returns a command that runs with a fake fzf on `PATH`, which selects the
+/// first entry it is given with Ctrl-D and exits without reading the rest.
+#[cfg(unix)]
+fn zoxide_remove_first(data_dir: &Path, bin_dir: &Path) -> Command {
+    use std::os::unix::fs::PermissionsExt;
+
+    let fzf = bin_dir.join("fzf");
+    let script = "#!/bin/sh\n\
+        if [ \"$1\" = --version ]; then echo 0.50.0; exit; fi\n\
+        printf 'ctrl-d\\0'\n\
+        head -z -n1\n";
+    fs::write(&fzf, script).unwrap();
+    fs::set_permissions(&fzf, fs::Permissions::from_mode(0o755)).unwrap();
+    let path = env::var_os("PATH").unwrap_or_default();
+    let path = env::join_paths(iter::once(bin_dir.to_path_buf()).chain(env::split_paths(&path)));
+
+    let mut cmd = zoxide(data_dir);
+    cmd.env("PATH", path.unwrap())
+        .env("_ZO_PICKER", "fzf")
+        .env_remove("_ZO_FZF_OPTS")
+        .timeout(Duration::from_secs(10));
+    cmd
+}
+
+/// Returns every path in the database.
+fn list(data_dir: &Path) -> Vec<String> {
+    let output = zoxide(data_dir).args(["query", "--list", "--all"]).output().unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap().lines().map(String::from).collect()
+}
+
+#[test]
+fn query_read_only() {
+    let data_dir = tempfile::tempdir().unwrap();
+    let dirs = tempfile::tempdir().unwrap();
+    for name in ["alpha", "beta"] {
+        let path = dirs.path().join(name);
+        fs::create_dir(&path).unwrap();
+        zoxide(data_dir.path()).arg("add").arg(&path).assert().success();
+    }
+
+    let before = snapshot(data_dir.path());
+    assert!(!before.is_empty());
+    for args in [&["query", "--list"][..], &["query", "--list", "--score", "a"]] {
+        zoxide(data_dir.path()).args(args).assert().success();
+    }
+    assert_eq!(snapshot(data_dir.path()), before);
+
+    // Jumping to the best match only appends what was learned to the journal.
+    zoxide(data_dir.path()).args(["query", "alpha"]).assert().success();
+    let after = snapshot(data_dir.path());
+    assert_eq!(after.get("db.zo"), before.get("db.zo"));
+    assert_ne!(after.get("db.zo.journal"), before.get("db.zo.journal"));
+    let output =
+        zoxide(data_dir.path()).args(["query", "--list", "--explain", "alpha"]).output().unwrap();
+    assert!(String::from_utf8(output.stdout).unwrap().contains("learned from previous jumps"));
+}
+
+#[test]
+fn query_subdir() {
+    let data_dir = tempfile::tempdir().unwrap();
+    let dirs = tempfile::tempdir().unwrap();
+    let proj = dirs.path().join("proj");
+    for path in ["proj/Src/main", "proj/src-old", "proj/docs", "other/src"] {
+        fs::create_dir_all(dirs.path().join(path)).unwrap();
+    }
+    fs::write(proj.join("srcfile"), "").unwrap();
+    zoxide(data_dir.path()).arg("add").arg(&proj).assert().success();
+
+    let query = |args: &[&str]| {
+        let output = zoxide(data_dir.path()).arg("query").args(args).output().unwrap();
+        output.status.success().then(|| String::from_utf8(output.stdout).unwrap())
+    };
+    let path = |path: &str| format!("{}\n", dirs.path().join(path).display());
+
+    // Components are matched ignoring case, preferring exact matches over
+    // longer names, and files are skipped.
+    assert_eq!(query(&["proj", "src"]), Some(path("proj/Src")));
+    assert_eq!(query(&["proj", "src", "ma"]), Some(path("proj/Src/main")));
+    assert_eq!(query(&["proj", "src/main"]), Some(path("proj/Src/main")));
+    assert_eq!(query(&["proj", "old"]), Some(path("proj/src-old")));
+    assert_eq!(query(&["proj", "d*"]), Some(path("proj/docs")));
+    // Smart case makes uppercase components case-sensitive.
+    assert_eq!(query(&["--smart-case", "proj", "SRC"]), None);
+    // Subdirectories can't escape the match.
+    assert_eq!(query(&["proj", "../other"]), None);
+    assert_eq!(query(&["proj", "missing"]), None);
+
+    // The subdirectory is scored like the directory it was found in. Querying
+    // the directory itself learns from it, so it is queried last.
+    let subdir = query(&["--score", "proj", "src"]).unwrap();
+    let score = query(&["--score", "proj"]).unwrap();
+    let score = score.split_whitespace().next().unwrap();
+    assert_eq!(subdir.split_whitespace().next(), Some(score));
+    assert_ne!(score, "0.0");
+}
+
+#[test]
+#[cfg(unix)]
+fn query_parent() {
+    let data_dir = tempfile::tempdir().unwrap();
+    let dirs = tempfile::tempdir().unwrap();
+    let dirs = dirs.path();
+    let current_dir = dirs.join("quux/corge/grault");
+    fs::create_dir_all(&current_dir).unwrap();
+    std::os::unix::fs::symlink(dirs.join("quux"), dirs.join("link")).unwrap();
+    let link_dir = dirs.join("link/corge/grault");
+
+    let query = |pwd: &Path, args: &[&str]| {
+        let output = zoxide(data_dir.path())
+            .current_dir(&current_dir)
+            .env("PWD", pwd)
+            .args(["query", "--parent"])
+            .args(args)
+            .output()
+            .unwrap();
+        output.status.success().then(|| String::from_utf8(output.stdout).unwrap())
+    };
+    let path = |path: &str| Some(format!("{}\n", dirs.join(path).display()));
+
+    // Parents are taken from $PWD, which may go through symlinks.
+    assert_eq!(query(&link_dir, &["link"]), path("link"));
+    assert_eq!(query(&link_dir, &["corge"]), path("link/corge"));
+    // A stale $PWD is ignored.
+    assert_eq!(query(Path::new("/"), &["quux"]), path("quux"));
+    assert_eq!(query(&current_dir, &["link"]), None);
+
+    // Excluded parents are skipped.
+    let exclude = dirs.join("link/corge");
+    let exclude = exclude.to_str().unwrap();
+    assert_eq!(query(&link_dir, &["--exclude", exclude, "corge"]), None);
+    assert_eq!(query(&link_dir, &["--exclude", exclude, "link"]), path("link"));
+}
+
+#[test]
+fn query_format() {
+    let data_dir = tempfile::tempdir().unwrap();
+    let dirs = tempfile::tempdir().unwrap();
+    let (alpha, beta) = (dirs.path().join("alpha"), dirs.path().join("beta"));
+    fs::create_dir(&alpha).unwrap();
+    fs::create_dir(&beta).unwrap();
+    zoxide(data_dir.path()).arg("add").arg(&alpha).arg(&beta).assert().success();
+    zoxide(data_dir.path()).arg("add").arg(&alpha).assert().success();
+    fs::remove_dir(&beta).unwrap();
+
+    let query = |args: &[&str]| {
+        let output = zoxide(data_dir.path()).arg("query").args(args).output().unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let check = |record: &serde_json::Value, path: &Path, rank: f64, exists: bool| {
+        assert_eq!(record["path"], path.to_str().unwrap());
+        assert_eq!(record["rank"], rank);
+        assert!(record["score"].as_f64().unwrap() > 0.0);
+        assert!(record["last_accessed"].as_u64().unwrap() > 0);
+        assert_eq!(record["pinned"], false);
+        assert_eq!(record["exists"], exists);
+    };
+
+    // A single result is an object.
+    let record = serde_json::from_str(&query(&["--format", "json", "a"])).unwrap();
+    check(&record, &alpha, 2.0, true);
+
+    // With --list, json prints an array and jsonl prints one result per line.
+    let records: Vec<serde_json::Value> =
+        serde_json::from_str(&query(&["--list", "--all", "--format", "json"])).unwrap();
+    assert_eq!(records.len(), 2);
+    check(&records[0], &alpha, 2.0, true);
+    check(&records[1], &beta, 1.0, false);
+
+    let output = query(&["--list", "--all", "--format", "jsonl"]);
+    let records = output
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect::<Vec<serde_json::Value>>();
+    assert_eq!(records.len(), 2);
+    check(&records[0], &alpha, 2.0, true);
+    check(&records[1], &beta, 1.0, false);
+
+    // Missing directories are left out without --all.
+    let output = query(&["--list", "--format", "jsonl"]);
+    assert_eq!(output.lines().count(), 1);
+}
+
+#[test]
+#[cfg(unix)]
+fn query_multi_remove() {
+    let data_dir = tempfile::tempdir().unwrap();
+    let dirs = tempfile::tempdir().unwrap();
+    let bin_dir = tempfile::tempdir().unwrap();
+    let (foo1, foo2) = (dirs.path().join("foo1"), dirs.path().join("foo2"));
+    fs::create_dir(&foo1).unwrap();
+    fs::create_dir(&foo2).unwrap();
+    zoxide(data_dir.path()).arg("add").arg(&foo1).arg(&foo2).assert().success();
+
+    zoxide_remove_first(data_dir.path(), bin_dir.path())
+        .args(["query", "--interactive", "--multi", "foo1"])
+        .assert()
+        .success();
+    assert_eq!(list(data_dir.path()), [foo2.to_str().unwrap()]);
+}
+
+#[test]
+#[cfg(unix)]
+fn query_multi_remove_early() {
+    let data_dir = tempfile::tempdir().unwrap();
+    let bin_dir = tempfile::tempdir().unwrap();
+
+    // Enough entries that the picker exits before all of them are written.
+    let now = SystemTime::now().duration_since(SystemTime::UNIX_EPOCH).unwrap().as_secs();
+    let entries = (0..3000)
+        .map(|idx| {
+            let path = format!("/zoxide-test/{}/{idx}", "x".repeat(100));
+            format!("{{\"path\": \"{path}\", \"rank\": 1.0, \"last_accessed\": {now}}}\n")
+        })
+        .collect::<String>();
+    let import = data_dir.path().join("import.jsonl");
+    fs::write(&import, entries).unwrap();
+    zoxide(data_dir.path()).args(["import", "json"]).arg(&import).assert().success();
+
+    zoxide_remove_first(data_dir.path(), bin_dir.path())
+        .args(["query", "--interactive", "--all", "--multi"])
+        .assert()
+        .success();
+    assert_eq!(list(data_dir.path()).len(), 2999);
+}
+
+#[test]
+fn query_alias() {
+    let data_dir = tempfile::tempdir().unwrap();
+    let dirs = tempfile::tempdir().unwrap();
+    let path = dirs.path().to_str().unwrap();
+    zoxide(data_dir.path()).args(["alias", "add", "api", path]).assert().success();
+
+    let query = |args: &[&str]| {
+        let output = zoxide(data_dir.path()).arg("query").args(args).output().unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap()
+    };
+    assert_eq!(query(&["@api"]), format!("{path}\n"));
+    assert_eq!(query(&["--list", "--print0", "@api"]), format!("{path}\0"));
+    assert_eq!(query(&["--score", "@api"]), format!("   0.0  {path}\n"));
+}
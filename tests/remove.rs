@@ -0,0 +1,84 @@
+//! Tests for `zoxide remove --forget`.
+
+use std::fs;
+use std::path::Path;
+
+use assert_cmd::Command;
+
+fn zoxide(data_dir: &Path) -> Command {
+    let mut cmd = Command::cargo_bin("zoxide").unwrap();
+    cmd.env("_ZO_DATA_DIR", data_dir)
+        .env("_ZO_EXCLUDE_DIRS", "")
+        .env_remove("_ZO_RESOLVE_SYMLINKS");
+    cmd
+}
+
+/// Returns the output of `zoxide` with `args`, one entry per line.
+fn lines(data_dir: &Path, args: &[&str]) -> Vec<String> {
+    let output = zoxide(data_dir).args(args).output().unwrap();
+    let mut lines =
+        String::from_utf8(output.stdout).unwrap().lines().map(String::from).collect::<Vec<_>>();
+    lines.sort();
+    lines
+}
+
+#[test]
+fn forget_literal() {
+    let data_dir = tempfile::tempdir().unwrap();
+    let dirs = tempfile::tempdir().unwrap();
+    let [brackets, plain] = ["a[1]", "a1"].map(|name| {
+        let path = dirs.path().join(name);
+        fs::create_dir(&path).unwrap();
+        path.to_str().unwrap().to_string()
+    });
+    zoxide(data_dir.path()).args(["add", &brackets, &plain]).assert().success();
+
+    // Glob characters in the path of a directory are matched literally.
+    zoxide(data_dir.path()).args(["remove", "--forget", &brackets]).assert().success();
+    assert_eq!(lines(data_dir.path(), &["query", "--list", "--all"]), [plain.as_str()]);
+    zoxide(data_dir.path()).args(["add", &brackets, &plain]).assert().success();
+    assert_eq!(lines(data_dir.path(), &["query", "--list", "--all"]), [plain.as_str()]);
+    assert_eq!(lines(data_dir.path(), &["remove", "--forgotten"]).len(), 1);
+
+    // The directory can be unforgotten by its path.
+    zoxide(data_dir.path()).args(["remove", "--unforget", &brackets]).assert().success();
+    assert!(lines(data_dir.path(), &["remove", "--forgotten"]).is_empty());
+    zoxide(data_dir.path()).args(["add", &brackets]).assert().success();
+    assert_eq!(lines(data_dir.path(), &["query", "--list", "--all"]).len(), 2);
+
+    // Paths that aren't directories are globs.
+    let glob = dirs.path().join("a*");
+    zoxide(data_dir.path()).args(["remove", "--forget"]).arg(&glob).assert().success();
+    assert!(lines(data_dir.path(), &["query", "--list", "--all"]).is_empty());
+    let forgotten = lines(data_dir.path(), &["remove", "--forgotten"]);
+    assert_eq!(forgotten, [glob.to_str().unwrap()]);
+}
+
+#[test]
+#[cfg(unix)]
+fn forget_resolve_symlinks() {
+    let data_dir = tempfile::tempdir().unwrap();
+    let dirs = tempfile::tempdir().unwrap();
+    let target = dirs.path().join("target");
+    let link = dirs.path().join("link");
+    fs::create_dir(&target).unwrap();
+    std::os::unix::fs::symlink(&target, &link).unwrap();
+
+    zoxide(data_dir.path())
+        .env("_ZO_RESOLVE_SYMLINKS", "1")
+        .args(["remove", "--forget"])
+        .arg(&link)
+        .assert()
+        .success();
+    let target = fs::canonicalize(&target).unwrap();
+    assert_eq!(lines(data_dir.path(), &["remove", "--forgotten"]), [target.to_str().unwrap()]);
+
+    // Adding through the link resolves to the forgotten target.
+    zoxide(data_dir.path())
+        .env("_ZO_RESOLVE_SYMLINKS", "1")
+        .arg("add")
+        .arg(&link)
+        .assert()
+        .success();
+    assert!(lines(data_dir.path(), &["query", "--list", "--all"]).is_empty());
+}